@@ -1,75 +1,124 @@
-use itertools::Itertools;
-use std::iter::Sum;
+use std::{
+    io::{self, BufRead},
+    iter::Sum,
+};
 use utils::prelude::*;
 
 fn main() {
     setup_logging();
-    let input: Vec<String> = read_input_lines().expect("Input could not be read");
+    let lines = read_input().expect("Input could not be read").lines();
 
-    let part_one = part_one(&input);
+    let Calibration(part_one, part_two) =
+        solve_streaming(lines, |l| Some(Calibration::from_line(l)))
+            .expect("Input could not be read");
     show_part_one(part_one);
-
-    let part_two = part_two(&input);
     show_part_two(part_two);
 }
 
+/// Calibration values of a line for both parts, so a single pass over the input solves both.
+#[derive(Debug, Default, PartialEq)]
+struct Calibration(u32, u32);
+
+impl Calibration {
+    fn from_line(input: &str) -> Self {
+        Calibration(
+            parse_line(input).unwrap_or(0),
+            parse_line_with_words(input).unwrap_or(0),
+        )
+    }
+}
+
+impl Sum for Calibration {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Calibration::default(), |total, c| {
+            Calibration(total.0 + c.0, total.1 + c.1)
+        })
+    }
+}
+
+/// Part one only counts digits while part two also counts spelled-out numbers,
+/// so the same input generally yields different values for both parts.
+#[cfg(test)]
+fn solve_both(input: &[String]) -> (u32, u32) {
+    (part_one(input), part_two(input))
+}
+
+#[cfg(test)]
 #[tracing::instrument(level = "info", ret(), skip_all)]
 fn part_one(input: &[String]) -> u32 {
     solve(input, parse_line)
 }
 
+#[cfg(test)]
 #[tracing::instrument(level = "info", ret(), skip_all)]
 fn part_two(input: &[String]) -> u32 {
     solve(input, parse_line_with_words)
 }
 
+#[cfg(test)]
 fn solve<F, R>(input: &[String], m: F) -> R
 where
     F: Fn(&str) -> Option<R>,
     R: Sum<R>,
 {
-    input.iter().filter_map(|l| m(l)).sum()
+    input.iter().filter_map(|l| m(l.trim_end())).sum()
+}
+
+fn solve_streaming<F, R>(lines: impl Iterator<Item = io::Result<String>>, m: F) -> SolutionResult<R>
+where
+    F: Fn(&str) -> Option<R>,
+    R: Sum<R>,
+{
+    lines
+        .filter_map(|l| l.map(|l| m(l.trim_end())).transpose())
+        .sum::<io::Result<R>>()
+        .map_err(SolutionError::from)
 }
 
 #[tracing::instrument(level = "debug", ret())]
 fn parse_line(input: &str) -> Option<u32> {
-    input
-        .chars()
-        .filter_map(|c| c.to_digit(10))
-        .fold(None, |e, d| match e {
-            Some((start, _)) => Some((start, d)),
-            None => Some((d, d)),
-        })
-        .map(|(a, b)| a * 10 + b)
+    first_and_last_digit(input, true, &[])
 }
 
-const NUMBERS: [&str; 9] = [
-    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+const NUMBERS: [(&str, u32); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
 ];
 
 #[tracing::instrument(level = "debug", ret())]
 fn parse_line_with_words(input: &str) -> Option<u32> {
-    fn inner(input: &str, start: Option<u32>, end: Option<u32>) -> Option<u32> {
-        if input.is_empty() {
-            start.zip(end).map(|(a, b)| a * 10 + b)
-        } else {
-            let digit: Option<u32> =
-                input
-                    .chars()
-                    .next()
-                    .and_then(|c| c.to_digit(10))
-                    .or_else(|| {
-                        NUMBERS
-                            .iter()
-                            .find_position(|&word| input.starts_with(word))
-                            .map(|(i, _)| i as u32 + 1)
-                    });
-
-            inner(&input[1..], start.or(digit), digit.or(end))
-        }
-    }
+    first_and_last_digit(input, true, &NUMBERS)
+}
 
-    inner(input, None, None)
+#[cfg(test)]
+#[tracing::instrument(level = "debug", ret())]
+fn parse_line_words_only(input: &str) -> Option<u32> {
+    first_and_last_digit(input, false, &NUMBERS)
+}
+
+fn first_and_last_digit(input: &str, digits: bool, words: &[(&str, u32)]) -> Option<u32> {
+    input
+        .char_indices()
+        .filter_map(|(i, c)| {
+            c.to_digit(10).filter(|_| digits).or_else(|| {
+                words
+                    .iter()
+                    .find(|(word, _)| input[i..].starts_with(word))
+                    .map(|&(_, value)| value)
+            })
+        })
+        .fold(None, |e, d| match e {
+            Some((start, _)) => Some((start, d)),
+            None => Some((d, d)),
+        })
+        .map(|(a, b)| a * 10 + b)
 }
 
 #[cfg(test)]
@@ -109,6 +158,21 @@ treb7uchet"
         assert_eq!(142, part_one(&input))
     }
 
+    #[test]
+    fn test_solve_both() {
+        let sample_one = include_str!("../input/sample1.txt")
+            .lines()
+            .map(|s| s.to_owned())
+            .collect_vec();
+        let sample_two = include_str!("../input/sample2.txt")
+            .lines()
+            .map(|s| s.to_owned())
+            .collect_vec();
+
+        assert_eq!(142, solve_both(&sample_one).0);
+        assert_eq!(281, solve_both(&sample_two).1);
+    }
+
     #[test]
     fn test_parse_lines_with_words() {
         assert_eq!(
@@ -141,6 +205,31 @@ treb7uchet"
         );
     }
 
+    #[test]
+    fn test_first_and_last_digit_custom_words() {
+        let dutch = [("een", 1), ("twee", 2), ("drie", 3), ("acht", 8)];
+
+        assert_eq!(Some(83), first_and_last_digit("achtweedrie", true, &dutch));
+        assert_eq!(Some(12), first_and_last_digit("xeen9twee", true, &dutch));
+        assert_eq!(Some(99), first_and_last_digit("9one", true, &dutch));
+        assert_eq!(
+            Some(83),
+            first_and_last_digit("eightwothree", true, &NUMBERS)
+        );
+        assert_eq!(
+            Some(14),
+            first_and_last_digit("zoneight234", true, &NUMBERS)
+        );
+        assert_eq!(None, first_and_last_digit("one", true, &[]));
+    }
+
+    #[test]
+    fn test_parse_lines_words_only() {
+        assert_eq!(None, parse_line_words_only("a1b"));
+        assert_eq!(Some(11), parse_line_words_only("aone1"));
+        assert_eq!(Some(29), parse_line_words_only("two1nine"));
+    }
+
     #[test]
     fn test_part_two() {
         let input = "two1nine
@@ -156,4 +245,47 @@ zoneight234
 
         assert_eq!(281, part_two(&input))
     }
+
+    #[test]
+    fn test_trailing_carriage_return() {
+        let input = vec![
+            owned!("1abc2\r"),
+            owned!("xtwone3four\r\n"),
+            owned!("abcnine \t"),
+        ];
+
+        assert_eq!(12, part_one(&input[..1]));
+        assert_eq!(12 + 24 + 99, part_two(&input));
+    }
+
+    #[test]
+    fn test_solve_streaming() {
+        let lines = ["1abc2", "pqr3stu8vwx", "a1b2c3d4e5f", "treb7uchet"]
+            .into_iter()
+            .map(|l| Ok(owned!(l)));
+        assert_eq!(142, solve_streaming(lines, parse_line).unwrap());
+
+        let lines = vec![
+            Ok(owned!("1abc2")),
+            Err(std::io::Error::other("disk on fire")),
+            Ok(owned!("treb7uchet")),
+        ];
+        assert!(solve_streaming::<_, u32>(lines.into_iter(), parse_line).is_err());
+    }
+
+    #[test]
+    fn test_solve_streaming_both_parts() {
+        let sample = include_str!("../input/sample2.txt")
+            .lines()
+            .map(|s| s.to_owned())
+            .collect_vec();
+        let streamed = solve_streaming(sample.iter().map(|l| Ok(l.clone())), |l| {
+            Some(Calibration::from_line(l))
+        })
+        .unwrap();
+
+        let (one, two) = solve_both(&sample);
+        assert_eq!(Calibration(one, two), streamed);
+        assert_eq!(281, streamed.1);
+    }
 }
@@ -1,7 +1,6 @@
-use std::{cmp::max, fmt::Display, str::FromStr};
+use std::{cmp::max, collections::BTreeMap, fmt::Display, str::FromStr};
 
 use itertools::Itertools;
-use regex::Regex;
 use utils::prelude::*;
 
 fn main() -> SolutionResult<()> {
@@ -26,9 +25,54 @@ fn part_one(games: &[Game], constraint: &Cubes) -> u32 {
         .sum()
 }
 
+#[cfg(test)]
+fn part_one_report<'a>(games: &'a [Game], constraint: &Cubes) -> Vec<(usize, &'a Cubes)> {
+    games
+        .iter()
+        .filter_map(|g| g.first_violation(constraint).map(|grab| (g.index, grab)))
+        .inspect(|(index, grab)| tracing::debug!(game = index, "Grab {} does not fit", grab))
+        .collect()
+}
+
+#[cfg(test)]
+fn impossible_games(games: &[Game], constraint: &Cubes) -> Vec<usize> {
+    part_one_report(games, constraint)
+        .into_iter()
+        .map(|(index, _)| index)
+        .collect()
+}
+
 #[tracing::instrument(level = "info", ret(), skip_all)]
 fn part_two(games: &[Game]) -> u128 {
-    games.iter().map(|g| g.minimal_set().power() as u128).sum()
+    minimal_sets(games)
+        .into_iter()
+        .map(|(_, _, power)| power as u128)
+        .sum()
+}
+
+#[cfg(test)]
+fn power_breakdown(games: &[Game]) -> (u128, u128, u128) {
+    minimal_sets(games)
+        .into_iter()
+        .fold((0, 0, 0), |(red, green, blue), (_, minimal, _)| {
+            (
+                red + minimal.red as u128,
+                green + minimal.green as u128,
+                blue + minimal.blue as u128,
+            )
+        })
+}
+
+fn minimal_sets(games: &[Game]) -> Vec<(usize, Cubes, usize)> {
+    games
+        .iter()
+        .map(|g| {
+            let minimal = g.minimal_set();
+            let power = minimal.power();
+            tracing::debug!(game = g.index, power, "Minimal set is {}", minimal);
+            (g.index, minimal, power)
+        })
+        .collect()
 }
 
 #[derive(Debug)]
@@ -39,7 +83,20 @@ struct Game {
 
 impl Game {
     fn fits_in(&self, constraint: &Cubes) -> bool {
-        self.grabs.iter().all(|g| g.fits_in(constraint))
+        self.first_violation(constraint).is_none()
+    }
+
+    fn first_violation(&self, constraint: &Cubes) -> Option<&Cubes> {
+        self.grabs.iter().find(|g| !g.fits_in(constraint))
+    }
+
+    #[cfg(test)]
+    fn fits_in_map(&self, constraint: &std::collections::HashMap<String, usize>) -> bool {
+        self.grabs.iter().all(|g| {
+            g.colors()
+                .iter()
+                .all(|(color, count)| constraint.get(*color).is_none_or(|max| count <= max))
+        })
     }
 
     fn minimal_set(&self) -> Cubes {
@@ -47,6 +104,27 @@ impl Game {
             .iter()
             .fold(Cubes::empty(), |minimal, cubes| minimal.union(cubes))
     }
+
+    #[cfg(test)]
+    fn from_str_at(s: &str, fallback_index: usize) -> Result<Self, SolutionError> {
+        let captures = capture_regex(compiled(r"^Game ?(?<index>\d*) ?: (?<cubes>.+)$"), s)?;
+
+        let index = match named_match(&captures, "index")? {
+            "" => fallback_index,
+            index => index.parse()?,
+        };
+
+        Ok(Game {
+            index,
+            grabs: Self::parse_grabs(named_match(&captures, "cubes")?)?,
+        })
+    }
+
+    fn parse_grabs(s: &str) -> Result<Vec<Cubes>, SolutionError> {
+        s.split("; ")
+            .map(|cube_string| cube_string.parse())
+            .try_collect()
+    }
 }
 
 impl Display for Game {
@@ -61,17 +139,13 @@ struct Cubes {
     red: usize,
     green: usize,
     blue: usize,
+    others: BTreeMap<String, usize>,
 }
 
 impl Display for Cubes {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let inputs = [
-            ("red", self.red),
-            ("green", self.green),
-            ("blue", self.blue),
-        ];
-
-        let output = inputs
+        let output = self
+            .colors()
             .iter()
             .filter(|&&(_, c)| c > 0)
             .map(|(color, count)| format!("{} {}", count, color))
@@ -82,11 +156,42 @@ impl Display for Cubes {
 
 impl Cubes {
     fn new(red: usize, green: usize, blue: usize) -> Self {
-        Self { red, green, blue }
+        Self {
+            red,
+            green,
+            blue,
+            others: BTreeMap::new(),
+        }
+    }
+
+    fn colors(&self) -> Vec<(&str, usize)> {
+        [
+            ("red", self.red),
+            ("green", self.green),
+            ("blue", self.blue),
+        ]
+        .into_iter()
+        .chain(
+            self.others
+                .iter()
+                .map(|(color, &count)| (color.as_str(), count)),
+        )
+        .collect()
+    }
+
+    fn count(&self, color: &str) -> usize {
+        match color {
+            "red" => self.red,
+            "green" => self.green,
+            "blue" => self.blue,
+            _ => self.others.get(color).copied().unwrap_or(0),
+        }
     }
 
     fn fits_in(&self, other: &Cubes) -> bool {
-        self.red <= other.red && self.green <= other.green && self.blue <= other.blue
+        self.colors()
+            .into_iter()
+            .all(|(color, count)| count <= other.count(color))
     }
 
     fn empty() -> Self {
@@ -105,15 +210,42 @@ impl Cubes {
         Self { blue, ..self }
     }
 
+    fn with_color(mut self, color: &str, count: usize) -> Result<Self, SolutionError> {
+        match color {
+            "red" => Ok(self.with_red(count)),
+            "green" => Ok(self.with_green(count)),
+            "blue" => Ok(self.with_blue(count)),
+            _ if color.is_empty() || !color.chars().all(|c| c.is_ascii_alphabetic()) => Err(
+                SolutionError::InputParsingFailed(format!("Invalid color: '{}'", color)),
+            ),
+            _ => {
+                if count == 0 {
+                    self.others.remove(color);
+                } else {
+                    self.others.insert(color.to_owned(), count);
+                }
+                Ok(self)
+            }
+        }
+    }
+
     fn power(&self) -> usize {
-        self.red * self.green * self.blue
+        self.colors().into_iter().map(|(_, count)| count).product()
     }
 
     fn union(&self, other: &Cubes) -> Cubes {
-        Cubes::new(
-            max(self.red, other.red),
-            max(self.green, other.green),
-            max(self.blue, other.blue),
+        other.others.keys().chain(self.others.keys()).fold(
+            Cubes::new(
+                max(self.red, other.red),
+                max(self.green, other.green),
+                max(self.blue, other.blue),
+            ),
+            |union, color| {
+                let count = max(self.count(color), other.count(color));
+                union
+                    .with_color(color, count)
+                    .expect("Colors of existing cubes are valid")
+            },
         )
     }
 }
@@ -133,30 +265,15 @@ impl FromStr for Cubes {
                     .ok_or_else(|| {
                         SolutionError::InputParsingFailed(format!("Could not parse '{}'", string))
                     })
-                    .and_then(|(count, color)| {
-                        if color == "red" || color == "green" || color == "blue" {
-                            Ok((color, count.parse()?))
-                        } else {
-                            Err(SolutionError::InputParsingFailed(format!(
-                                "Unknown color encountered: '{}'",
-                                color
-                            )))
-                        }
-                    })
+                    .and_then(|(count, color)| Ok((color, count.parse()?)))
             })
             .try_collect()?;
 
-        Ok(colors
-            .iter()
-            .fold(Cubes::empty(), |cubes, &(color, count)| {
-                if color == "red" {
-                    cubes.with_red(count)
-                } else if color == "green" {
-                    cubes.with_green(count)
-                } else {
-                    cubes.with_blue(count)
-                }
-            }))
+        colors
+            .into_iter()
+            .try_fold(Cubes::empty(), |cubes, (color, count)| {
+                cubes.with_color(color, count)
+            })
     }
 }
 
@@ -164,7 +281,7 @@ impl FromStr for Game {
     type Err = SolutionError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let regex = Regex::new(r"^Game (?<index>\d+): (?<cubes>.+)$").unwrap();
+        let regex = compiled(r"^Game (?<index>\d+): (?<cubes>.+)$");
         let captures = regex.captures(s).ok_or_else(|| {
             SolutionError::InputParsingFailed("Regular expression didn't match input".to_owned())
         })?;
@@ -176,19 +293,16 @@ impl FromStr for Game {
             })?
             .as_str()
             .parse()?;
-        let cubes: Vec<Cubes> = captures
+        let cubes = captures
             .name("cubes")
             .ok_or_else(|| {
                 SolutionError::InputParsingFailed("Could not find 'cubes' match".to_owned())
             })?
-            .as_str()
-            .split("; ")
-            .map(|cube_string| cube_string.parse())
-            .try_collect()?;
+            .as_str();
 
         Ok(Game {
             index,
-            grabs: cubes,
+            grabs: Self::parse_grabs(cubes)?,
         })
     }
 }
@@ -196,18 +310,21 @@ impl FromStr for Game {
 #[cfg(test)]
 mod test {
 
+    use std::collections::HashMap;
+
     use super::*;
+    use utils::testing::run_day_against;
 
     #[test]
     fn test_cubes_fit_in() {
-        assert_eq!(true, Cubes::new(0, 0, 0).fits_in(&Cubes::new(0, 0, 0)));
-        assert_eq!(true, Cubes::new(0, 0, 0).fits_in(&Cubes::new(1, 1, 1)));
-        assert_eq!(true, Cubes::new(1, 2, 3).fits_in(&Cubes::new(2, 3, 4)));
-
-        assert_eq!(false, Cubes::new(1, 2, 3).fits_in(&Cubes::new(2, 2, 2)));
-        assert_eq!(false, Cubes::new(2, 3, 1).fits_in(&Cubes::new(2, 2, 2)));
-        assert_eq!(false, Cubes::new(3, 1, 2).fits_in(&Cubes::new(2, 2, 2)));
-        assert_eq!(false, Cubes::new(3, 3, 3).fits_in(&Cubes::new(2, 2, 2)));
+        assert!(Cubes::new(0, 0, 0).fits_in(&Cubes::new(0, 0, 0)));
+        assert!(Cubes::new(0, 0, 0).fits_in(&Cubes::new(1, 1, 1)));
+        assert!(Cubes::new(1, 2, 3).fits_in(&Cubes::new(2, 3, 4)));
+
+        assert!(!Cubes::new(1, 2, 3).fits_in(&Cubes::new(2, 2, 2)));
+        assert!(!Cubes::new(2, 3, 1).fits_in(&Cubes::new(2, 2, 2)));
+        assert!(!Cubes::new(3, 1, 2).fits_in(&Cubes::new(2, 2, 2)));
+        assert!(!Cubes::new(3, 3, 3).fits_in(&Cubes::new(2, 2, 2)));
     }
 
     #[test]
@@ -283,6 +400,27 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_cubes_other_colors() {
+        let cubes: Cubes = "1 red, 2 green, 3 blue, 4 yellow"
+            .parse()
+            .expect("Could not be parsed");
+
+        assert_eq!(24, cubes.power());
+        assert_eq!(4, cubes.count("yellow"));
+        assert_eq!("1 red, 2 green, 3 blue, 4 yellow", cubes.to_string());
+        assert!(!cubes.fits_in(&Cubes::new(12, 13, 14)));
+        assert!(Cubes::new(1, 2, 3).fits_in(&cubes));
+
+        let union = Cubes::new(2, 0, 0).union(&cubes);
+        assert_eq!(2 * 2 * 3 * 4, union.power());
+        assert_eq!(
+            Cubes::new(1, 0, 0),
+            "1 red, 0 yellow".parse().expect("Could not be parsed")
+        );
+        assert!("1 r3d".parse::<Cubes>().is_err());
+    }
+
     #[test]
     fn test_game_parsing() {
         let input = "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red";
@@ -299,6 +437,21 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_game_parsing_with_fallback() {
+        let game = Game::from_str_at("Game : 1 red", 7).expect("Could not parse game");
+        assert_eq!(7, game.index);
+        assert_eq!(vec![Cubes::new(1, 0, 0)], game.grabs);
+
+        let game = Game::from_str_at("Game 3: 1 red", 7).expect("Could not parse game");
+        assert_eq!(3, game.index);
+
+        assert!("Game : 1 red".parse::<Game>().is_err());
+        assert!("Game: 1 red".parse::<Game>().is_err());
+        assert!("Game 3 : 1 red".parse::<Game>().is_err());
+        assert!("Game3: 1 red".parse::<Game>().is_err());
+    }
+
     #[test]
     fn test_game_fits_in() {
         let constraint = Cubes::new(12, 13, 14);
@@ -311,8 +464,24 @@ mod test {
                 .parse()
                 .expect("Game could not be parsed");
 
-        assert_eq!(true, fit.fits_in(&constraint));
-        assert_eq!(false, no_fit.fits_in(&constraint));
+        assert!(fit.fits_in(&constraint));
+        assert!(!no_fit.fits_in(&constraint));
+    }
+
+    #[test]
+    fn test_game_fits_in_map() {
+        let game: Game = "Game 1: 3 blue, 4 red; 1 red, 2 green, 20 blue; 2 green"
+            .parse()
+            .expect("Game could not be parsed");
+
+        let constraint = HashMap::from([(owned!("red"), 4), (owned!("green"), 2)]);
+        assert!(game.fits_in_map(&constraint));
+
+        let constraint = HashMap::from([(owned!("red"), 3), (owned!("green"), 2)]);
+        assert!(!game.fits_in_map(&constraint));
+
+        let constraint = HashMap::from([(owned!("blue"), 19)]);
+        assert!(!game.fits_in_map(&constraint));
     }
 
     #[test]
@@ -327,4 +496,78 @@ mod test {
         assert_eq!(Cubes::new(4, 2, 6), game_1.minimal_set());
         assert_eq!(Cubes::new(1, 3, 4), game_2.minimal_set());
     }
+
+    #[test]
+    fn test_minimal_sets() {
+        let games: Vec<Game> = vec![
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green"
+                .parse()
+                .expect("Game could not be parsed"),
+            "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue"
+                .parse()
+                .expect("Game could not be parsed"),
+        ];
+
+        assert_eq!(
+            vec![(1, Cubes::new(4, 2, 6), 48), (2, Cubes::new(1, 3, 4), 12)],
+            minimal_sets(&games)
+        );
+        assert_eq!(60, part_two(&games));
+        assert_eq!((5, 5, 10), power_breakdown(&games));
+    }
+
+    #[test]
+    fn test_impossible_games() {
+        let games: Vec<Game> = include_str!("../input/sample.txt")
+            .lines()
+            .map(|l| l.parse())
+            .try_collect()
+            .expect("Sample could not be parsed");
+        let constraint = Cubes::new(12, 13, 14);
+
+        assert_eq!(vec![3, 4], impossible_games(&games, &constraint));
+        assert_eq!(
+            games.iter().map(|g| g.index as u32).sum::<u32>(),
+            part_one(&games, &constraint)
+                + impossible_games(&games, &constraint).iter().sum::<usize>() as u32
+        );
+    }
+
+    #[test]
+    fn test_first_violation() {
+        let games: Vec<Game> = include_str!("../input/sample.txt")
+            .lines()
+            .map(|l| l.parse())
+            .try_collect()
+            .expect("Sample could not be parsed");
+        let constraint = Cubes::new(12, 13, 14);
+
+        assert_eq!(None, games[0].first_violation(&constraint));
+        assert_eq!(
+            Some(&Cubes::new(20, 8, 6)),
+            games[2].first_violation(&constraint)
+        );
+
+        let report = part_one_report(&games, &constraint);
+        assert_eq!(
+            vec![3, 4],
+            report.iter().map(|(index, _)| *index).collect_vec()
+        );
+        assert_eq!(&Cubes::new(20, 8, 6), report[0].1);
+    }
+
+    #[test]
+    fn test_sample() {
+        run_day_against(include_str!("../input/sample.txt"), "8", "2286", |lines| {
+            let games: Vec<Game> = lines
+                .iter()
+                .map(|l| l.parse())
+                .try_collect()
+                .expect("Sample could not be parsed");
+            (
+                part_one(&games, &Cubes::new(12, 13, 14)).to_string(),
+                part_two(&games).to_string(),
+            )
+        });
+    }
 }
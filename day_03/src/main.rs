@@ -1,119 +1,221 @@
 use std::collections::HashMap;
 
 use itertools::Itertools;
-use utils::prelude::*;
+use utils::{geometry::Point, parsing::digits_to_number, prelude::*};
 
-type Parts = Vec<(HorizontalRange, u32)>;
+type Parts = Vec<(HorizontalRange, u64)>;
 type Symbols = HashMap<Point, char>;
 
 fn main() {
     setup_logging();
     let lines: Vec<String> = read_input_lines().expect("Could not read input");
-    let (parts, symbols) = parse_input(&lines);
+    let (parts, symbols) = parse_input(&lines).expect("Could not parse input");
 
     let part_one = part_one(&parts, &symbols);
     show_part_one(part_one);
 
     let part_two = part_two(&parts, &symbols);
-    show_part_two(part_two);
+    show_result_part_two(part_two);
 }
 
-fn part_one(parts: &Parts, symbols: &Symbols) -> u32 {
+fn part_one(parts: &Parts, symbols: &Symbols) -> u64 {
     parts
         .iter()
         .filter_map(|(hor_pos, num)| {
-            let perimeter: Vec<_> = hor_pos.perimeter();
-            if perimeter.iter().any(|pos| symbols.contains_key(pos)) {
-                Some(num)
-            } else {
+            if symbol_neighbors(hor_pos, symbols).is_empty() {
                 None
+            } else {
+                Some(num)
             }
         })
         .sum()
 }
 
-fn part_two(parts: &Parts, symbols: &Symbols) -> u32 {
-    symbols
-        .iter()
-        .filter_map(|(pos, c)| {
-            if *c != '*' {
-                None
-            } else {
-                let touching_parts = parts
-                    .iter()
-                    .filter_map(|(hpos, num)| if hpos.touches(pos) { Some(*num) } else { None })
-                    .collect_tuple();
-                touching_parts.map(|(a, b)| a * b)
-            }
-        })
-        .sum()
+fn symbol_neighbors(range: &HorizontalRange, symbols: &Symbols) -> Vec<(Point, char)> {
+    range
+        .perimeter::<Vec<Point>>()
+        .into_iter()
+        .filter_map(|pos| symbols.get(&pos).map(|&c| (pos, c)))
+        .collect()
 }
 
-fn parse_input(input: &[String]) -> (Parts, Symbols) {
-    input
+#[cfg(test)]
+fn detect_overlaps(parts: &Parts) -> Vec<(usize, usize)> {
+    parts
         .iter()
         .enumerate()
-        .fold((vec![], HashMap::new()), |(parts, symbols), (y, line)| {
-            let (mut parts, symbols, acc) = line.chars().enumerate().fold(
-                (parts, symbols, None),
-                |(mut parts, mut symbols, acc), (x, c)| {
-                    if c.is_ascii_digit() {
-                        let next_acc = match acc {
-                            None => (x, x, c.to_digit(10).unwrap()),
-                            Some((begin, _, val)) => (begin, x, val * 10 + c.to_digit(10).unwrap()),
-                        };
-
-                        (parts, symbols, Some(next_acc))
-                    } else {
-                        if let Some((start, end, num)) = acc {
-                            parts.push((HorizontalRange::new(start, end, y), num));
-                        }
-
-                        if c != '.' {
-                            symbols.insert(Point::new(x, y), c);
-                        }
-                        (parts, symbols, None)
-                    }
-                },
-            );
+        .tuple_combinations()
+        .filter(|((_, (a, _)), (_, (b, _)))| a.overlaps(b))
+        .map(|((i, _), (j, _))| (i, j))
+        .collect()
+}
 
-            if let Some((start, end, num)) = acc {
-                parts.push((HorizontalRange::new(start, end, y), num))
-            }
+fn part_two(parts: &Parts, symbols: &Symbols) -> SolutionResult<u64> {
+    gears(parts, symbols)?
+        .iter()
+        .try_fold(0u64, |total, (_, ratio)| total.checked_add(*ratio))
+        .ok_or_else(|| SolutionError::overflow("summing gear ratios"))
+}
+
+fn gears(parts: &Parts, symbols: &Symbols) -> SolutionResult<Vec<(Point, u64)>> {
+    gears_by(parts, symbols, gear_ratio)
+}
 
-            (parts, symbols)
+fn gears_by<F>(parts: &Parts, symbols: &Symbols, ratio: F) -> SolutionResult<Vec<(Point, u64)>>
+where
+    F: Fn(char, &[u64]) -> SolutionResult<Option<u64>>,
+{
+    let gears: Vec<(Point, u64)> = symbols
+        .iter()
+        .filter_map(|(pos, &symbol)| {
+            ratio(symbol, &adjacent_parts(pos, parts))
+                .map(|ratio| ratio.map(|ratio| (*pos, ratio)))
+                .transpose()
         })
+        .try_collect()?;
+
+    Ok(gears
+        .into_iter()
+        .sorted_by_key(|(pos, _)| (pos.y, pos.x))
+        .collect())
 }
 
-#[derive(Debug, PartialEq, Eq, Hash)]
-struct Point {
-    x: usize,
-    y: usize,
+fn adjacent_parts(pos: &Point, parts: &Parts) -> Vec<u64> {
+    parts
+        .iter()
+        .filter_map(|(hpos, num)| if hpos.touches(pos) { Some(*num) } else { None })
+        .collect()
 }
 
-impl Point {
-    fn new(x: usize, y: usize) -> Self {
-        Point { x, y }
+fn gear_ratio(symbol: char, adjacents: &[u64]) -> SolutionResult<Option<u64>> {
+    match adjacents {
+        [a, b] if symbol == '*' => a
+            .checked_mul(*b)
+            .map(Some)
+            .ok_or_else(|| SolutionError::overflow(&format!("gear ratio {} * {}", a, b))),
+        _ => Ok(None),
     }
 }
 
-impl<I> From<(I, I)> for Point
+#[cfg(test)]
+fn parse_and_solve_part_one_stream<I, S>(lines: I) -> SolutionResult<u64>
 where
-    I: Into<usize>,
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
 {
-    fn from(value: (I, I)) -> Self {
-        Point::new(value.0.into(), value.1.into())
+    use std::collections::HashSet;
+
+    fn symbol_columns(symbols: Vec<(Point, char)>) -> HashSet<i64> {
+        symbols.into_iter().map(|(p, _)| p.x).collect()
+    }
+
+    fn adjacent_sum(parts: &Parts, rows: [&HashSet<i64>; 3]) -> u64 {
+        parts
+            .iter()
+            .filter(|(range, _)| {
+                (range.min_x.saturating_sub(1)..=range.max_x + 1)
+                    .any(|x| rows.iter().any(|row| row.contains(&x)))
+            })
+            .map(|(_, num)| num)
+            .sum()
+    }
+
+    let (total, previous, current, pending) = lines.into_iter().zip(0..).try_fold(
+        (0, HashSet::new(), HashSet::new(), vec![]),
+        |(total, previous, current, pending), (line, y)| {
+            let (parts, symbols) = parse_row(y, line.as_ref())?;
+            let next = symbol_columns(symbols);
+            let total = total + adjacent_sum(&pending, [&previous, &current, &next]);
+            SolutionResult::Ok((total, current, next, parts))
+        },
+    )?;
+
+    Ok(total + adjacent_sum(&pending, [&previous, &current, &HashSet::new()]))
+}
+
+fn parse_input(input: &[String]) -> SolutionResult<(Parts, Symbols)> {
+    input.iter().zip(0..).try_fold(
+        (vec![], HashMap::new()),
+        |(mut parts, mut symbols), (line, y)| {
+            let (row_parts, row_symbols) = parse_row(y, line)?;
+            parts.extend(row_parts);
+            symbols.extend(row_symbols);
+            Ok((parts, symbols))
+        },
+    )
+}
+
+#[cfg(test)]
+fn parse_input_spans(input: &[String]) -> SolutionResult<Vec<(HorizontalRange, u64, String)>> {
+    input
+        .iter()
+        .zip(0..)
+        .map(|(line, y)| {
+            let (parts, _) = parse_row(y, line)?;
+            Ok(parts
+                .into_iter()
+                .map(|(range, num)| {
+                    let raw = line
+                        .chars()
+                        .skip(range.min_x as usize)
+                        .take((range.max_x - range.min_x + 1) as usize)
+                        .collect();
+                    (range, num, raw)
+                })
+                .collect_vec())
+        })
+        .flatten_ok()
+        .try_collect()
+}
+
+fn parse_row(y: i64, line: &str) -> SolutionResult<(Parts, Vec<(Point, char)>)> {
+    let (mut parts, symbols, acc) = line.chars().zip(0..).try_fold(
+        (vec![], vec![], None::<(i64, i64, Vec<u32>)>),
+        |(mut parts, mut symbols, acc), (c, x)| {
+            if let Some(digit) = c.to_digit(10) {
+                let next_acc = match acc {
+                    None => (x, x, vec![digit]),
+                    Some((begin, _, mut digits)) => {
+                        digits.push(digit);
+                        (begin, x, digits)
+                    }
+                };
+
+                SolutionResult::Ok((parts, symbols, Some(next_acc)))
+            } else {
+                if let Some((start, end, digits)) = acc {
+                    parts.push((
+                        HorizontalRange::new(start, end, y),
+                        digits_to_number(digits)?,
+                    ));
+                }
+
+                if c != '.' {
+                    symbols.push((Point::new(x, y), c));
+                }
+                Ok((parts, symbols, None))
+            }
+        },
+    )?;
+
+    if let Some((start, end, digits)) = acc {
+        parts.push((
+            HorizontalRange::new(start, end, y),
+            digits_to_number(digits)?,
+        ))
     }
+
+    Ok((parts, symbols))
 }
 
 struct HorizontalRange {
-    min_x: usize,
-    max_x: usize,
-    y: usize,
+    min_x: i64,
+    max_x: i64,
+    y: i64,
 }
 
 impl HorizontalRange {
-    fn new(min_x: usize, max_x: usize, y: usize) -> Self {
+    fn new(min_x: i64, max_x: i64, y: i64) -> Self {
         HorizontalRange { min_x, max_x, y }
     }
 
@@ -137,6 +239,11 @@ impl HorizontalRange {
             .collect()
     }
 
+    #[cfg(test)]
+    fn overlaps(&self, other: &HorizontalRange) -> bool {
+        self.y == other.y && self.min_x <= other.max_x && other.min_x <= self.max_x
+    }
+
     fn touches(&self, point: &Point) -> bool {
         if self.y == point.y {
             point.x + 1 == self.min_x || point.x == self.max_x + 1
@@ -150,7 +257,89 @@ impl HorizontalRange {
 
 #[cfg(test)]
 mod test {
+    use std::collections::HashSet;
+
     use super::*;
+    use itertools::iproduct;
+
+    #[test]
+    fn test_gear_ratio() {
+        assert_eq!(Some(16345), gear_ratio('*', &[467, 35]).unwrap());
+        assert_eq!(None, gear_ratio('*', &[467]).unwrap());
+        assert_eq!(None, gear_ratio('*', &[1, 2, 3]).unwrap());
+        assert_eq!(None, gear_ratio('#', &[467, 35]).unwrap());
+        assert!(matches!(
+            gear_ratio('*', &[u64::MAX, 2]),
+            Err(SolutionError::Overflow(_))
+        ));
+
+        let lines = vec![owned!("1.2"), owned!(".*."), owned!("..3")];
+        let (parts, symbols) = parse_input(&lines).expect("Parsing should work");
+        assert_eq!(vec![1, 2, 3], adjacent_parts(&Point::new(1, 1), &parts));
+        assert!(gears(&parts, &symbols).unwrap().is_empty());
+
+        let three = |symbol, adjacents: &[u64]| match adjacents {
+            [a, b, c] if symbol == '*' => Ok(Some(a * b * c)),
+            _ => Ok(None),
+        };
+        assert_eq!(
+            vec![(Point::new(1, 1), 6)],
+            gears_by(&parts, &symbols, three).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_input_spans() {
+        let lines = vec![owned!("....007*12"), owned!("3.........")];
+        let spans = parse_input_spans(&lines).expect("Parsing should work");
+
+        let summary = spans
+            .iter()
+            .map(|(range, num, raw)| (range.min_x, range.max_x, range.y, *num, raw.as_str()))
+            .collect_vec();
+        assert_eq!(
+            vec![(4, 6, 0, 7, "007"), (8, 9, 0, 12, "12"), (0, 0, 1, 3, "3")],
+            summary
+        );
+    }
+
+    #[test]
+    fn test_detect_overlaps() {
+        let parts: Parts = vec![
+            (HorizontalRange::new(0, 2, 0), 467),
+            (HorizontalRange::new(5, 7, 0), 114),
+            (HorizontalRange::new(2, 3, 1), 35),
+            (HorizontalRange::new(7, 8, 0), 58),
+            (HorizontalRange::new(3, 4, 1), 633),
+        ];
+
+        assert_eq!(vec![(1, 3), (2, 4)], detect_overlaps(&parts));
+
+        let lines = include_str!("../input/sample.txt")
+            .lines()
+            .map(|l| owned!(l))
+            .collect_vec();
+        let (parts, _) = parse_input(&lines).expect("Parsing should work");
+        assert!(detect_overlaps(&parts).is_empty());
+    }
+
+    #[test]
+    fn test_perimeter_matches_touches() {
+        for (min_x, length, y) in iproduct!(0..4, 1..4, 0..3) {
+            let range = HorizontalRange::new(min_x, min_x + length - 1, y);
+            let perimeter: HashSet<Point> = range.perimeter();
+            let touching: HashSet<Point> = iproduct!(0..10, 0..6)
+                .map(|(x, y)| Point::new(x, y))
+                .filter(|p| range.touches(p))
+                .collect();
+
+            assert_eq!(
+                perimeter, touching,
+                "perimeter and touches disagree for {}..={} on row {}",
+                range.min_x, range.max_x, range.y
+            );
+        }
+    }
 
     #[test]
     fn test_horiz_range_touches() {
@@ -161,9 +350,9 @@ mod test {
             .map(|x| Point::new(x, range.y))
             .collect_vec();
         assert!(intern.iter().all(|p| !range.touches(p)));
-        assert_eq!(false, range.touches(&Point::new(0, 3)));
-        assert_eq!(false, range.touches(&Point::new(5, 2)));
-        assert_eq!(false, range.touches(&Point::new(5, 1)));
+        assert!(!range.touches(&Point::new(0, 3)));
+        assert!(!range.touches(&Point::new(5, 2)));
+        assert!(!range.touches(&Point::new(5, 1)));
 
         let range = HorizontalRange::new(0, 2, 2);
         let perim: Vec<Point> = range.perimeter();
@@ -172,9 +361,9 @@ mod test {
             .map(|x| Point::new(x, range.y))
             .collect_vec();
         assert!(intern.iter().all(|p| !range.touches(p)));
-        assert_eq!(false, range.touches(&Point::new(4, 0)));
-        assert_eq!(false, range.touches(&Point::new(4, 2)));
-        assert_eq!(false, range.touches(&Point::new(4, 1)));
+        assert!(!range.touches(&Point::new(4, 0)));
+        assert!(!range.touches(&Point::new(4, 2)));
+        assert!(!range.touches(&Point::new(4, 1)));
 
         let range = HorizontalRange::new(0, 2, 0);
         let perim: Vec<Point> = range.perimeter();
@@ -183,16 +372,77 @@ mod test {
             .map(|x| Point::new(x, range.y))
             .collect_vec();
         assert!(intern.iter().all(|p| !range.touches(p)));
-        assert_eq!(false, range.touches(&Point::new(4, 0)));
-        assert_eq!(false, range.touches(&Point::new(4, 1)));
-        assert_eq!(false, range.touches(&Point::new(1, 2)));
+        assert!(!range.touches(&Point::new(4, 0)));
+        assert!(!range.touches(&Point::new(4, 1)));
+        assert!(!range.touches(&Point::new(1, 2)));
+    }
+
+    #[test]
+    fn test_symbol_neighbors() {
+        let lines = vec![owned!("#123*"), owned!(".....")];
+        let (parts, symbols) = parse_input(&lines).expect("Parsing should work");
+        let (range, num) = &parts[0];
+        assert_eq!(123, *num);
+
+        let neighbors = symbol_neighbors(range, &symbols)
+            .into_iter()
+            .sorted_by_key(|(p, _)| p.x)
+            .collect_vec();
+        assert_eq!(
+            vec![(Point::new(0, 0), '#'), (Point::new(4, 0), '*')],
+            neighbors
+        );
+    }
+
+    #[test]
+    fn test_gears() {
+        let lines = include_str!("../input/sample.txt")
+            .lines()
+            .map(|l| owned!(l))
+            .collect_vec();
+        let (parts, symbols) = parse_input(&lines).expect("Parsing should work");
+
+        assert_eq!(
+            vec![(Point::new(3, 1), 467 * 35), (Point::new(5, 8), 755 * 598)],
+            gears(&parts, &symbols).unwrap()
+        );
+        assert_eq!(467835, part_two(&parts, &symbols).unwrap());
+    }
+
+    #[test]
+    fn test_streaming_part_one() {
+        let lines = include_str!("../input/sample.txt")
+            .lines()
+            .map(|l| owned!(l))
+            .collect_vec();
+        let (parts, symbols) = parse_input(&lines).expect("Parsing should work");
+
+        assert_eq!(
+            part_one(&parts, &symbols),
+            parse_and_solve_part_one_stream(&lines).expect("Parsing should work")
+        );
+        assert_eq!(
+            4361,
+            parse_and_solve_part_one_stream(&lines).expect("Parsing should work")
+        );
+    }
+
+    #[test]
+    fn test_wide_part_number() {
+        let lines = vec![owned!("123456789012*."), owned!("..............")];
+        let (parts, symbols) = parse_input(&lines).expect("Parsing should work");
+
+        assert_eq!(123456789012, part_one(&parts, &symbols));
+
+        let lines = vec![owned!("123456789012345678901*.")];
+        assert!(parse_input(&lines).is_err());
     }
 
     #[test]
     fn test_horiz_range_perimeter() {
         let test: Vec<Point> = HorizontalRange::new(1, 3, 1).perimeter();
         assert_eq!(
-            vec![
+            [
                 (0, 0),
                 (1, 0),
                 (2, 0),
@@ -207,14 +457,14 @@ mod test {
                 (4, 2)
             ]
             .iter()
-            .map(|t: &(usize, usize)| Point::from(*t))
+            .map(|t: &(i64, i64)| Point::from(*t))
             .collect_vec(),
             test
         );
 
         let test: Vec<_> = HorizontalRange::new(1, 1, 1).perimeter();
         assert_eq!(
-            vec![
+            [
                 (0, 0),
                 (1, 0),
                 (2, 0),
@@ -225,34 +475,34 @@ mod test {
                 (2, 2)
             ]
             .iter()
-            .map(|t: &(usize, usize)| Point::from(*t))
+            .map(|t: &(i64, i64)| Point::from(*t))
             .collect_vec(),
             test
         );
 
         let test: Vec<_> = HorizontalRange::new(0, 1, 1).perimeter();
         assert_eq!(
-            vec![(0, 0), (1, 0), (2, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+            [(0, 0), (1, 0), (2, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
                 .iter()
-                .map(|t: &(usize, usize)| Point::from(*t))
+                .map(|t: &(i64, i64)| Point::from(*t))
                 .collect_vec(),
             test
         );
 
         let test: Vec<_> = HorizontalRange::new(1, 3, 0).perimeter();
         assert_eq!(
-            vec![(0, 0), (4, 0), (0, 1), (1, 1), (2, 1), (3, 1), (4, 1)]
+            [(0, 0), (4, 0), (0, 1), (1, 1), (2, 1), (3, 1), (4, 1)]
                 .iter()
-                .map(|t: &(usize, usize)| Point::from(*t))
+                .map(|t: &(i64, i64)| Point::from(*t))
                 .collect_vec(),
             test
         );
 
         let test: Vec<_> = HorizontalRange::new(0, 1, 0).perimeter();
         assert_eq!(
-            vec![(2, 0), (0, 1), (1, 1), (2, 1)]
+            [(2, 0), (0, 1), (1, 1), (2, 1)]
                 .iter()
-                .map(|t: &(usize, usize)| Point::from(*t))
+                .map(|t: &(i64, i64)| Point::from(*t))
                 .collect_vec(),
             test
         );
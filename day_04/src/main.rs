@@ -9,44 +9,82 @@ use utils::prelude::*;
 fn main() {
     let cards: Vec<Card> = parse_input_lines().expect("Input could not be read");
 
-    let part_one: u32 = part_one(&cards);
-    show_part_one(part_one);
+    let part_one = part_one(&cards);
+    show_result_part_one(part_one);
 
     let part_two = part_two(&cards);
     show_part_two(part_two);
 }
 
 #[tracing::instrument(level = "info", ret(), skip_all)]
-fn part_one(cards: &[Card]) -> u32 {
+fn part_one(cards: &[Card]) -> SolutionResult<u64> {
     cards.iter().map(|c| c.score()).sum()
 }
 
 #[tracing::instrument(level = "info", ret(), skip_all)]
-fn part_two(cards: &[Card]) -> u32 {
+fn part_two(cards: &[Card]) -> u64 {
+    card_copies(cards).values().sum()
+}
+
+fn card_copies(cards: &[Card]) -> HashMap<usize, u64> {
     let max_index = cards.last().map(|c| c.index).unwrap_or(0);
-    let (card_count, _) = cards
-        .iter()
-        .fold((0, HashMap::new()), |(total_count, copies), card| {
-            let matching_number_count = card.matching_numbers_count();
-            let current_card_count = *copies.get(&card.index).unwrap_or(&1);
-
-            let copies = (1..=matching_number_count)
-                .map(|i| i + card.index)
-                .filter(|i| i <= &max_index)
-                .fold(copies, |copies, update_index| {
+    cards.iter().fold(HashMap::new(), |copies, card| {
+        let matching_number_count = card.matching_numbers_count();
+        let current_card_count = *copies.get(&card.index).unwrap_or(&1);
+
+        (1..=matching_number_count)
+            .map(|i| i + card.index)
+            .filter(|i| i <= &max_index)
+            .fold(
+                copies.update(card.index, current_card_count),
+                |copies, update_index| {
                     copies.alter(
                         |value| Some(value.unwrap_or(1) + current_card_count),
                         update_index,
                     )
-                });
+                },
+            )
+    })
+}
 
-            (total_count + current_card_count, copies)
-        });
+#[cfg(test)]
+fn ranked_cards(cards: &[Card]) -> SolutionResult<Vec<(usize, u64)>> {
+    let scored: Vec<(usize, u64)> = cards
+        .iter()
+        .map(|c| c.score().map(|score| (c.index, score)))
+        .try_collect()?;
 
-    card_count
+    Ok(scored
+        .into_iter()
+        .sorted_by(|(a_index, a_score), (b_index, b_score)| {
+            b_score.cmp(a_score).then(a_index.cmp(b_index))
+        })
+        .collect())
 }
 
-#[derive(Debug)]
+#[cfg(test)]
+fn merge_cards(a: Vec<Card>, b: Vec<Card>) -> SolutionResult<Vec<Card>> {
+    use std::collections::BTreeMap;
+
+    a.into_iter()
+        .chain(b)
+        .try_fold(
+            BTreeMap::new(),
+            |mut merged: BTreeMap<usize, Card>, card| match merged.get(&card.index) {
+                Some(existing) if existing != &card => Err(SolutionError::InputParsingFailed(
+                    format!("Conflicting definitions for card {}", card.index),
+                )),
+                Some(_) => Ok(merged),
+                None => {
+                    merged.insert(card.index, card);
+                    Ok(merged)
+                }
+            },
+        )
+        .map(|merged| merged.into_values().collect())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct Card {
     index: usize,
     winning_numbers: HashSet<u32>,
@@ -71,25 +109,27 @@ impl Display for Card {
 
 impl Card {
     #[tracing::instrument(level = "trace", ret())]
-    fn score(&self) -> u32 {
-        self.picked_numbers
-            .iter()
-            .fold(None, |sum, number| {
-                if self.winning_numbers.contains(number) {
-                    Some(sum.map(|s| s * 2).unwrap_or(1))
-                } else {
-                    sum
-                }
-            })
-            .unwrap_or(0)
+    fn score(&self) -> SolutionResult<u64> {
+        match self.matching_numbers_count() {
+            0 => Ok(0),
+            count => u32::try_from(count - 1)
+                .ok()
+                .and_then(|shift| 1u64.checked_shl(shift))
+                .ok_or_else(|| SolutionError::overflow(&format!("score of card {}", self.index))),
+        }
     }
 
     #[tracing::instrument(level = "trace", ret())]
-    fn matching_numbers_count(&self) -> usize {
+    fn matches(&self) -> Vec<u32> {
         self.picked_numbers
             .iter()
             .filter(|n| self.winning_numbers.contains(n))
-            .count()
+            .copied()
+            .collect()
+    }
+
+    fn matching_numbers_count(&self) -> usize {
+        self.matches().len()
     }
 }
 
@@ -103,7 +143,7 @@ impl FromStr for Card {
         });
 
         let m = capture_regex(&RE, s)?;
-        let index: usize = named_match(&m, "index")?.parse()?;
+        let index: usize = named_match_parsed(&m, "index")?;
 
         let winning = named_match(&m, "winning")?;
         let picked = named_match(&m, "picked")?;
@@ -147,32 +187,77 @@ mod test {
         assert_eq!(vec![61, 30, 68, 82, 17, 32, 24, 19], card.picked_numbers);
     }
 
+    #[test]
+    fn test_card_equality() {
+        let card: Card = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53"
+            .parse()
+            .expect("Parsing didn't work");
+        let expected = Card {
+            index: 1,
+            winning_numbers: HashSet::from([41, 48, 83, 86, 17]),
+            picked_numbers: vec![83, 86, 6, 31, 17, 9, 48, 53],
+        };
+
+        assert_eq!(expected, card);
+        assert_ne!(
+            Card {
+                index: 2,
+                ..expected.clone()
+            },
+            card
+        );
+    }
+
     #[test]
     fn test_score_calculation() {
         let card: Card = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53"
             .parse()
             .expect("Parsing didn't work");
-        assert_eq!(8, card.score());
+        assert_eq!(8, card.score().unwrap());
         let card: Card = "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19"
             .parse()
             .expect("Parsing didn't work");
-        assert_eq!(2, card.score());
+        assert_eq!(2, card.score().unwrap());
         let card: Card = "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1"
             .parse()
             .expect("Parsing didn't work");
-        assert_eq!(2, card.score());
+        assert_eq!(2, card.score().unwrap());
         let card: Card = "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83"
             .parse()
             .expect("Parsing didn't work");
-        assert_eq!(1, card.score());
+        assert_eq!(1, card.score().unwrap());
         let card: Card = "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36"
             .parse()
             .expect("Parsing didn't work");
-        assert_eq!(0, card.score());
+        assert_eq!(0, card.score().unwrap());
         let card: Card = "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"
             .parse()
             .expect("Parsing didn't work");
-        assert_eq!(0, card.score());
+        assert_eq!(0, card.score().unwrap());
+    }
+
+    #[test]
+    fn test_score_many_matches() {
+        let card = |count: u32| {
+            let numbers = (1..=count).collect_vec();
+            Card {
+                index: 1,
+                winning_numbers: numbers.iter().copied().collect(),
+                picked_numbers: numbers,
+            }
+        };
+
+        assert_eq!(1 << 40, card(41).score().unwrap());
+        assert_eq!(1 << 63, card(64).score().unwrap());
+        assert!(matches!(card(65).score(), Err(SolutionError::Overflow(_))));
+    }
+
+    #[test]
+    fn test_matches() {
+        let card: Card = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53"
+            .parse()
+            .expect("Parsing didn't work");
+        assert_eq!(vec![83, 86, 17, 48], card.matches());
     }
 
     #[test]
@@ -200,6 +285,81 @@ mod test {
         let card: Card = "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"
             .parse()
             .expect("Parsing didn't work");
-        assert_eq!(0, card.score());
+        assert_eq!(0, card.score().unwrap());
+    }
+
+    #[test]
+    fn test_ranked_cards() {
+        let cards: Vec<Card> = include_str!("../input/sample.txt")
+            .lines()
+            .map(|l| l.parse())
+            .try_collect()
+            .expect("Parsing didn't work");
+
+        assert_eq!(
+            vec![(1, 8), (2, 2), (3, 2), (4, 1), (5, 0), (6, 0)],
+            ranked_cards(&cards).expect("Scoring didn't work")
+        );
+    }
+
+    #[test]
+    fn test_merge_cards() {
+        let a: Vec<Card> = vec![
+            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1"
+                .parse()
+                .expect("Parsing didn't work"),
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53"
+                .parse()
+                .expect("Parsing didn't work"),
+        ];
+        let b: Vec<Card> = vec!["Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19"
+            .parse()
+            .expect("Parsing didn't work")];
+
+        let merged = merge_cards(a, b).expect("Merging should work");
+        assert_eq!(vec![1, 2, 3], merged.iter().map(|c| c.index).collect_vec());
+    }
+
+    #[test]
+    fn test_merge_conflicting_cards() {
+        let a: Vec<Card> = vec!["Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53"
+            .parse()
+            .expect("Parsing didn't work")];
+        let b: Vec<Card> = vec!["Card 1: 13 32 20 16 61 | 61 30 68 82 17 32 24 19"
+            .parse()
+            .expect("Parsing didn't work")];
+
+        assert!(merge_cards(a, b).is_err());
+    }
+
+    #[test]
+    fn test_card_copies() {
+        let cards: Vec<Card> = include_str!("../input/sample.txt")
+            .lines()
+            .map(|l| l.parse())
+            .try_collect()
+            .expect("Parsing didn't work");
+
+        let copies = card_copies(&cards);
+        assert_eq!(6, copies.len());
+        assert_eq!(Some(&1), copies.get(&1));
+        assert_eq!(Some(&8), copies.get(&4));
+        assert_eq!(30, part_two(&cards));
+    }
+
+    #[test]
+    fn test_part_two_large_totals() {
+        let cards = (1..=33)
+            .map(|index| {
+                let numbers = (1..=(33 - index) as u32).collect_vec();
+                Card {
+                    index,
+                    winning_numbers: numbers.iter().copied().collect(),
+                    picked_numbers: numbers,
+                }
+            })
+            .collect_vec();
+
+        assert_eq!((1u64 << 33) - 1, part_two(&cards));
     }
 }
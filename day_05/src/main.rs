@@ -2,6 +2,7 @@ use derive_more::{Deref, From, Into};
 use im::{vector, Vector};
 use std::{
     cmp::{max, min},
+    collections::HashMap,
     fmt::Display,
     marker::PhantomData,
     ops::Deref,
@@ -16,6 +17,7 @@ fn main() {
 
     let input: Vec<String> = read_input_lines().expect("Could not read input");
     let (seeds, translation) = parse_input(&input).expect("Could not parse input");
+    let translation = translation.simplify();
 
     let part_one = part_one(&seeds, &translation);
     show_result_part_one(part_one);
@@ -46,14 +48,21 @@ fn part_two(
         .sorted_by_key(|(a, _)| *a)
         .collect_vec();
 
-    let lowest = translation
+    let rules: Vec<_> = translation
         .translation
         .collapse_table()
         .0
         .into_iter()
-        .sorted_by_key(|rule| rule.destination_range().0)
-        .fold_while(None, |lowest, rule| {
-            let (dst_start, _) = rule.destination_range();
+        .map(|rule| {
+            rule.checked_destination_range()
+                .map(|(dst_start, _)| (dst_start, rule))
+        })
+        .try_collect()?;
+
+    let lowest = rules
+        .into_iter()
+        .sorted_by_key(|(dst_start, _)| *dst_start)
+        .fold_while(None, |lowest, (dst_start, rule)| {
             if lowest.map_or_else(|| true, |lowest_location| dst_start < lowest_location) {
                 let rule_range = rule.source_range();
                 if let Some(seed_range) = seed_ranges
@@ -80,6 +89,63 @@ fn part_two(
     }
 }
 
+#[cfg(test)]
+fn location_histogram(
+    seeds: &[Seed],
+    translation: &TypedTranslation<Seed, Location>,
+) -> SolutionResult<Vec<((u64, u64), u64)>> {
+    let seed_ranges = seeds
+        .iter()
+        .tuples()
+        .map(|(start, length)| (start.0, start.0 + length.0 - 1))
+        .collect_vec();
+
+    let histogram: Vec<_> = translation
+        .translation
+        .collapsed()
+        .0
+        .into_iter()
+        .map(|rule| {
+            let count = seed_ranges
+                .iter()
+                .filter(|&&seed_range| {
+                    TranslationRule::overlaps_with(seed_range, rule.source_range())
+                })
+                .map(|&(start, end)| min(end, rule.end) - max(start, rule.start) + 1)
+                .sum();
+            rule.checked_destination_range()
+                .map(|destination| (destination, count))
+        })
+        .try_collect()?;
+
+    Ok(histogram.into_iter().sorted().collect())
+}
+
+#[cfg(test)]
+fn seed_for_location(
+    location: u64,
+    translation: &TypedTranslation<Seed, Location>,
+    seeds: &[Seed],
+) -> Option<Seed> {
+    let seed_ranges = seeds
+        .iter()
+        .tuples()
+        .map(|(start, length)| (start.0, start.0 + length.0 - 1))
+        .collect_vec();
+
+    translation
+        .translation
+        .untranslate(&location)
+        .into_iter()
+        .sorted()
+        .find(|seed| {
+            seed_ranges
+                .iter()
+                .any(|&(start, end)| seed >= &start && seed <= &end)
+        })
+        .map(Seed)
+}
+
 fn parse_input(lines: &[String]) -> SolutionResult<(Vec<Seed>, TypedTranslation<Seed, Location>)> {
     let mut iter = lines.iter();
     let seeds: Vec<Seed> = iter
@@ -93,19 +159,36 @@ fn parse_input(lines: &[String]) -> SolutionResult<(Vec<Seed>, TypedTranslation<
 
     tracing::debug!("Parsed seeds: {}", seeds.iter().join(" "));
 
-    let tables: Vec<Translation> = iter
+    let mut tables: HashMap<String, (String, Translation)> = iter
         .skip(1)
         .batching(|i| {
             if let Some(title) = i.next() {
-                let result: SolutionResult<Translation> = (|| {
+                let result: SolutionResult<(String, (String, Translation))> = (|| {
                     tracing::debug!("Parsing table {}", title);
+                    let (source, destination) = title
+                        .strip_suffix(" map:")
+                        .and_then(|t| t.split_once("-to-"))
+                        .ok_or_else(|| {
+                            SolutionError::InputParsingFailed(format!(
+                                "Malformed table title: {}",
+                                title
+                            ))
+                        })?;
+
                     let rules: Vector<TranslationRule> = i
                         .take_while(|l| !l.is_empty())
                         .map(|line| line.parse())
                         .try_collect()?;
 
                     tracing::debug!("Done parsing table {}, found {} rules", title, rules.len());
-                    Ok(Translation::new(rules))
+                    let translation = Translation::new(rules);
+                    translation
+                        .tables()
+                        .into_iter()
+                        .try_for_each(TranslationTable::validate)
+                        .map_err(|e| e.with_context(title))?;
+
+                    Ok((source.to_owned(), (destination.to_owned(), translation)))
                 })();
 
                 Some(result)
@@ -113,28 +196,46 @@ fn parse_input(lines: &[String]) -> SolutionResult<(Vec<Seed>, TypedTranslation<
                 None
             }
         })
-        .try_collect()?;
+        .try_fold(HashMap::new(), |mut tables, table| {
+            let (source, table) = table?;
+            if tables.contains_key(&source) {
+                return Err(SolutionError::InputParsingFailed(format!(
+                    "Found more than one table translating from {}",
+                    source
+                )));
+            }
+            tables.insert(source, table);
+            Ok(tables)
+        })?;
+
+    let mut stage = owned!("seed");
+    let mut translations = vec![];
+    while let Some((destination, translation)) = tables.remove(&stage) {
+        translations.push(translation);
+        stage = destination;
+    }
 
-    let (
-        seed2soil,
-        soil2fertilizer,
-        fertilizer2water,
-        water2light,
-        light2temperature,
-        temperature2humidity,
-        humidity2location,
-    ) = tables.into_iter().collect_tuple().ok_or_else(|| {
-        SolutionError::InputParsingFailed(owned!("Incorrect number of translation tables"))
-    })?;
-
-    let translation = seed2soil
-        .typed::<Seed, Soil>()
-        .and_then(soil2fertilizer.typed::<Soil, Fertilizer>())
-        .and_then(fertilizer2water.typed::<Fertilizer, Water>())
-        .and_then(water2light.typed::<Water, Light>())
-        .and_then(light2temperature.typed::<Light, Temperature>())
-        .and_then(temperature2humidity.typed::<Temperature, Humidity>())
-        .and_then(humidity2location.typed::<Humidity, Location>());
+    if stage != "location" {
+        return Err(SolutionError::InputParsingFailed(format!(
+            "Translation tables end at {} instead of location",
+            stage
+        )));
+    }
+
+    if !tables.is_empty() {
+        return Err(SolutionError::InputParsingFailed(format!(
+            "Unexpected translation tables: {}",
+            tables.keys().join(", ")
+        )));
+    }
+
+    let translation = translations
+        .into_iter()
+        .reduce(Translation::and_then)
+        .ok_or_else(|| SolutionError::InputParsingFailed(owned!("No translation tables found")))?
+        .typed::<Seed, Location>();
+
+    translation.translation.validate_chain()?;
 
     Ok((seeds, translation))
 }
@@ -154,19 +255,13 @@ impl Display for Location {
     }
 }
 
+#[cfg(test)]
 #[derive(Deref, From)]
 struct Soil(u64);
+#[cfg(test)]
 #[derive(Deref, From)]
 struct Fertilizer(u64);
-#[derive(Deref, From)]
-struct Water(u64);
-#[derive(Deref, From)]
-struct Light(u64);
-#[derive(Deref, From)]
-struct Temperature(u64);
-#[derive(Deref, From)]
-struct Humidity(u64);
-#[derive(Deref, From, PartialEq, PartialOrd, Eq, Ord)]
+#[derive(Deref, From, PartialEq, PartialOrd, Eq, Ord, Debug)]
 struct Location(u64);
 
 enum Translation {
@@ -197,9 +292,35 @@ impl<I: Deref<Target = u64>, O: From<u64>> TypedTranslation<I, O> {
     fn transform(&self, input: &I) -> O {
         self.translation.translate(input).into()
     }
+
+    fn simplify(self) -> Self {
+        TypedTranslation {
+            translation: self.translation.simplify(),
+            input: PhantomData,
+            output: PhantomData,
+        }
+    }
+
+    #[cfg(test)]
+    fn transform_range(&self, input: (u64, u64)) -> Vec<(u64, u64)> {
+        self.translation.map_range(input)
+    }
 }
 
 impl<I: Deref<Target = u64>, O: Deref<Target = u64> + From<u64>> TypedTranslation<I, O> {
+    #[cfg(test)]
+    fn reverse(self) -> SolutionResult<TypedTranslation<O, I>>
+    where
+        I: From<u64>,
+    {
+        Ok(TypedTranslation {
+            translation: self.translation.reverse()?,
+            input: PhantomData,
+            output: PhantomData,
+        })
+    }
+
+    #[cfg(test)]
     fn and_then<N>(self, other: TypedTranslation<O, N>) -> TypedTranslation<I, N>
     where
         N: From<u64>,
@@ -272,6 +393,19 @@ impl TranslationRule {
         )
     }
 
+    fn checked_destination_range(&self) -> SolutionResult<(u64, u64)> {
+        let shift = |value: u64| {
+            value.checked_add_signed(self.delta).ok_or_else(|| {
+                SolutionError::InputParsingFailed(format!(
+                    "Destination of rule {}..{} with delta {} is out of range",
+                    self.start, self.end, self.delta
+                ))
+            })
+        };
+
+        Ok((shift(self.start)?, shift(self.end)?))
+    }
+
     fn length(&self) -> u64 {
         self.end - self.start + 1
     }
@@ -281,7 +415,7 @@ impl TranslationRule {
     }
 
     fn split(&self, length: u64) -> (TranslationRule, TranslationRule) {
-        if length == 0 || self.start + length >= self.end {
+        if length == 0 || length >= self.length() {
             panic!("length must > 0 and < {}, got, {}", self.length(), length)
         }
 
@@ -333,6 +467,21 @@ impl TranslationTable {
         TranslationTable(rules.into_iter().sorted().collect())
     }
 
+    fn validate(&self) -> SolutionResult<()> {
+        match self
+            .0
+            .iter()
+            .tuple_windows()
+            .find(|(a, b)| TranslationRule::overlaps_with(a.source_range(), b.source_range()))
+        {
+            Some((a, b)) => Err(SolutionError::InputParsingFailed(format!(
+                "Rules '{}' and '{}' have overlapping sources",
+                a, b
+            ))),
+            None => Ok(()),
+        }
+    }
+
     fn translate(&self, input: &u64) -> u64 {
         let (output, rule) = self
             .0
@@ -348,6 +497,36 @@ impl TranslationTable {
         output
     }
 
+    #[cfg(test)]
+    fn to_triples(&self) -> Vec<(u64, u64, u64)> {
+        self.0
+            .iter()
+            .map(|rule| (rule.destination_range().0, rule.start, rule.length()))
+            .collect()
+    }
+
+    #[cfg(test)]
+    fn untranslate(&self, output: &u64) -> Vec<u64> {
+        let mut inputs = self
+            .0
+            .iter()
+            .filter_map(|rule| {
+                let (dst_start, dst_end) = rule.destination_range();
+                if output >= &dst_start && output <= &dst_end {
+                    Some((*output as i64 - rule.delta) as u64)
+                } else {
+                    None
+                }
+            })
+            .collect_vec();
+
+        if self.0.iter().all(|rule| rule.translate(output).is_none()) {
+            inputs.push(*output);
+        }
+
+        inputs
+    }
+
     fn map(&self, rule: TranslationRule) -> Vector<TranslationRule> {
         let result = self.0.iter().fold_while(
             (Vector::<TranslationRule>::new(), Some(rule)),
@@ -400,6 +579,15 @@ impl TranslationTable {
         rules.into_iter().sorted().collect()
     }
 
+    #[cfg(test)]
+    fn map_range(&self, (start, end): (u64, u64)) -> Vec<(u64, u64)> {
+        self.map(TranslationRule::new(start, end, 0))
+            .iter()
+            .map(|rule| rule.destination_range())
+            .sorted()
+            .collect()
+    }
+
     fn clear(self, input_range: (u64, u64)) -> Self {
         let updated_rules: Vec<_> = self
             .0
@@ -412,14 +600,14 @@ impl TranslationTable {
                 } else {
                     let mut result = vector![];
 
-                    if input_range.0 > rule.start && input_range.0 < rule.end {
+                    if input_range.0 > rule.start {
                         result.push_back(TranslationRule {
                             end: input_range.0 - 1,
                             ..rule
                         })
                     }
 
-                    if input_range.1 > rule.start && input_range.1 < rule.end {
+                    if input_range.1 < rule.end {
                         result.push_back(TranslationRule {
                             start: input_range.1 + 1,
                             ..rule
@@ -440,6 +628,47 @@ impl TranslationTable {
         result
     }
 
+    /// Only a table that maps the values its rules cover onto exactly those same values has an
+    /// inverse, otherwise some output would have several inputs.
+    #[cfg(test)]
+    fn reverse(&self) -> SolutionResult<Self> {
+        let rules: Vec<_> = self
+            .0
+            .iter()
+            .map(|rule| {
+                rule.checked_destination_range()
+                    .map(|(dst_start, dst_end)| {
+                        TranslationRule::new(dst_start, dst_end, -rule.delta)
+                    })
+            })
+            .try_collect()?;
+        let reversed = TranslationTable::new(rules);
+
+        reversed
+            .validate()
+            .map_err(|e| e.with_context("Destinations of the table overlap"))?;
+        if reversed.coverage() != self.coverage() {
+            return Err(SolutionError::InputParsingFailed(owned!(
+                "Table does not map its source ranges onto themselves and can not be reversed"
+            )));
+        }
+
+        Ok(reversed)
+    }
+
+    #[cfg(test)]
+    fn coverage(&self) -> Vec<(u64, u64)> {
+        self.0
+            .iter()
+            .fold(vec![], |mut ranges: Vec<(u64, u64)>, rule| {
+                match ranges.last_mut() {
+                    Some(last) if rule.start <= last.1 + 1 => last.1 = max(last.1, rule.end),
+                    _ => ranges.push(rule.source_range()),
+                }
+                ranges
+            })
+    }
+
     fn fold(self, other: Self) -> Self {
         self.0
             .into_iter()
@@ -456,6 +685,43 @@ impl Translation {
         }
     }
 
+    #[cfg(test)]
+    fn untranslate(&self, output: &u64) -> Vec<u64> {
+        match self {
+            Self::Table(table) => table.untranslate(output),
+            Self::Chain(a, b) => b
+                .untranslate(output)
+                .iter()
+                .flat_map(|intermediate| a.untranslate(intermediate))
+                .collect(),
+        }
+    }
+
+    #[cfg(test)]
+    fn map_rules(&self, rules: Vector<TranslationRule>) -> Vector<TranslationRule> {
+        match self {
+            Self::Table(table) => rules.into_iter().flat_map(|rule| table.map(rule)).collect(),
+            Self::Chain(a, b) => b.map_rules(a.map_rules(rules)),
+        }
+    }
+
+    #[cfg(test)]
+    fn map_range(&self, (start, end): (u64, u64)) -> Vec<(u64, u64)> {
+        self.map_rules(vector![TranslationRule::new(start, end, 0)])
+            .iter()
+            .map(|rule| rule.destination_range())
+            .sorted()
+            .collect()
+    }
+
+    #[cfg(test)]
+    fn reverse(self) -> SolutionResult<Translation> {
+        Ok(match self {
+            Self::Table(table) => Self::Table(table.reverse()?),
+            Self::Chain(a, b) => Self::Chain(Box::new(b.reverse()?), Box::new(a.reverse()?)),
+        })
+    }
+
     fn and_then(self, other: Translation) -> Translation {
         Translation::Chain(Box::new(self), Box::new(other))
     }
@@ -472,6 +738,51 @@ impl Translation {
         }
     }
 
+    fn simplify(self) -> Translation {
+        match self {
+            Self::Table(_) => self,
+            Self::Chain(_, _) => Self::Table(self.collapse_table()),
+        }
+    }
+
+    fn tables(&self) -> Vec<&TranslationTable> {
+        match self {
+            Self::Table(table) => vec![table],
+            Self::Chain(a, b) => a.tables().into_iter().chain(b.tables()).collect(),
+        }
+    }
+
+    #[cfg(test)]
+    fn stages(&self, input: u64) -> impl Iterator<Item = u64> + '_ {
+        std::iter::once(input).chain(self.tables().into_iter().scan(input, |value, table| {
+            *value = table.translate(value);
+            Some(*value)
+        }))
+    }
+
+    fn validate_chain(&self) -> SolutionResult<()> {
+        let tables = self.tables();
+
+        if let Some(index) = tables.iter().position(|table| table.0.is_empty()) {
+            return Err(SolutionError::InputParsingFailed(format!(
+                "Translation table {} of {} has no rules",
+                index + 1,
+                tables.len()
+            )));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(test)]
+    fn collapsed(&self) -> TranslationTable {
+        self.tables()
+            .into_iter()
+            .cloned()
+            .reduce(|a, b| a.fold(b))
+            .unwrap_or_else(|| TranslationTable::new(vec![]))
+    }
+
     fn collapse_table(self) -> TranslationTable {
         match self {
             Self::Table(table) => table,
@@ -483,23 +794,29 @@ impl Translation {
 #[cfg(test)]
 mod test {
     use im::vector;
+    use proptest::prelude::*;
     use utils::io::output::setup_logging;
 
-    use crate::{TranslationRule, TranslationTable};
+    use utils::{owned, result::SolutionError};
+
+    use crate::{
+        location_histogram, parse_input, part_one, part_two, seed_for_location, Fertilizer,
+        Location, Seed, Soil, Translation, TranslationRule, TranslationTable,
+    };
 
     #[test]
     fn test_rule_overlap() {
-        assert_eq!(true, TranslationRule::overlaps_with((1, 5), (5, 9)));
-        assert_eq!(true, TranslationRule::overlaps_with((1, 6), (5, 9)));
-        assert_eq!(true, TranslationRule::overlaps_with((1, 9), (5, 6)));
-        assert_eq!(true, TranslationRule::overlaps_with((1, 9), (5, 9)));
-        assert_eq!(true, TranslationRule::overlaps_with((5, 9), (1, 6)));
-        assert_eq!(true, TranslationRule::overlaps_with((5, 9), (1, 5)));
-        assert_eq!(true, TranslationRule::overlaps_with((5, 6), (1, 9)));
-        assert_eq!(true, TranslationRule::overlaps_with((5, 9), (1, 9)));
-
-        assert_eq!(false, TranslationRule::overlaps_with((1, 4), (5, 8)));
-        assert_eq!(false, TranslationRule::overlaps_with((5, 8), (1, 4)));
+        assert!(TranslationRule::overlaps_with((1, 5), (5, 9)));
+        assert!(TranslationRule::overlaps_with((1, 6), (5, 9)));
+        assert!(TranslationRule::overlaps_with((1, 9), (5, 6)));
+        assert!(TranslationRule::overlaps_with((1, 9), (5, 9)));
+        assert!(TranslationRule::overlaps_with((5, 9), (1, 6)));
+        assert!(TranslationRule::overlaps_with((5, 9), (1, 5)));
+        assert!(TranslationRule::overlaps_with((5, 6), (1, 9)));
+        assert!(TranslationRule::overlaps_with((5, 9), (1, 9)));
+
+        assert!(!TranslationRule::overlaps_with((1, 4), (5, 8)));
+        assert!(!TranslationRule::overlaps_with((5, 8), (1, 4)));
     }
 
     #[test]
@@ -515,6 +832,41 @@ mod test {
         assert_eq!((3, 7), b.source_range());
         assert_eq!((1, 5), b.destination_range());
         assert_eq!(5, b.length());
+
+        assert_eq!(
+            (TranslationRule::new(1, 4, 2), TranslationRule::new(5, 5, 2)),
+            a.split(4)
+        );
+    }
+
+    #[test]
+    fn test_checked_destination_range() {
+        assert_eq!(
+            (3, 7),
+            TranslationRule::new(1, 5, 2)
+                .checked_destination_range()
+                .expect("Range should fit")
+        );
+        assert!(TranslationRule::new(u64::MAX - 5, u64::MAX, 10)
+            .checked_destination_range()
+            .is_err());
+        assert!(TranslationRule::new(0, 5, -1)
+            .checked_destination_range()
+            .is_err());
+    }
+
+    #[test]
+    fn test_translation_table_to_triples() {
+        let table = TranslationTable::new(vec![
+            "0 15 37".parse().unwrap(),
+            "37 52 2".parse().unwrap(),
+            "39 0 15".parse().unwrap(),
+        ]);
+
+        assert_eq!(
+            vec![(39, 0, 15), (0, 15, 37), (37, 52, 2)],
+            table.to_triples()
+        );
     }
 
     #[test]
@@ -569,6 +921,53 @@ mod test {
         assert_eq!(vector![TranslationRule::new(0, 10, 40)], result);
     }
 
+    #[test]
+    fn test_translation_table_validate() {
+        let table = TranslationTable::new(vec![
+            "50 98 2".parse().unwrap(),
+            "52 50 48".parse().unwrap(),
+        ]);
+        assert!(table.validate().is_ok());
+
+        let table = TranslationTable::new(vec![
+            TranslationRule::new(10, 19, 5),
+            TranslationRule::new(15, 24, -5),
+        ]);
+        assert!(table.validate().is_err());
+
+        let input = ["seeds: 1", "", "seed-to-soil map:", "10 0 5", "20 4 5"].map(|l| owned!(l));
+        assert!(matches!(
+            parse_input(&input),
+            Err(SolutionError::InputParsingFailed(msg)) if msg.contains("seed-to-soil map:") && msg.contains("overlapping")
+        ));
+    }
+
+    #[test]
+    fn test_translation_table_map_range() {
+        let table = TranslationTable::new(vec![
+            TranslationRule::new(10, 19, 10),
+            TranslationRule::new(21, 29, 20),
+        ]);
+
+        assert_eq!(
+            vec![(20, 20), (25, 29), (41, 45)],
+            table.map_range((15, 25))
+        );
+        assert_eq!(vec![(0, 5)], table.map_range((0, 5)));
+        assert_eq!(vec![(5, 9), (20, 22)], table.map_range((5, 12)));
+    }
+
+    #[test]
+    fn test_translation_map_range() {
+        let translation = Translation::new(vec![TranslationRule::new(10, 19, 10)])
+            .and_then(Translation::new(vec![TranslationRule::new(21, 29, -20)]));
+
+        assert_eq!(
+            vec![(1, 5), (5, 9), (20, 20)],
+            translation.map_range((5, 15))
+        );
+    }
+
     #[test]
     fn test_translation_table_clear() {
         let table = TranslationTable::new(vector![
@@ -637,6 +1036,274 @@ mod test {
 
         let result = table.clone().clear((10, 39));
         assert_eq!(result.0, vector![]);
+
+        let result = TranslationTable::new(vec![TranslationRule::new(43, 51, -34)]).clear((43, 43));
+        assert_eq!(result.0, vector![TranslationRule::new(44, 51, -34)]);
+
+        let result = TranslationTable::new(vec![TranslationRule::new(43, 51, -34)]).clear((51, 60));
+        assert_eq!(result.0, vector![TranslationRule::new(43, 50, -34)]);
+    }
+
+    #[test]
+    fn test_parse_shuffled_tables() {
+        let input = [
+            "seeds: 79 14 55 13",
+            "",
+            "humidity-to-location map:",
+            "60 56 37",
+            "56 93 4",
+            "",
+            "soil-to-fertilizer map:",
+            "0 15 37",
+            "37 52 2",
+            "39 0 15",
+            "",
+            "water-to-light map:",
+            "88 18 7",
+            "18 25 70",
+            "",
+            "seed-to-soil map:",
+            "50 98 2",
+            "52 50 48",
+            "",
+            "light-to-temperature map:",
+            "45 77 23",
+            "81 45 19",
+            "68 64 13",
+            "",
+            "fertilizer-to-water map:",
+            "49 53 8",
+            "0 11 42",
+            "42 0 7",
+            "57 7 4",
+            "",
+            "temperature-to-humidity map:",
+            "0 69 1",
+            "1 0 69",
+        ]
+        .map(|l| owned!(l));
+
+        let (seeds, translation) = parse_input(&input).expect("Parsing should work");
+        let lowest = part_one(&seeds, &translation).expect("Solution should be found");
+        assert_eq!(Location(35), lowest);
+
+        let missing = input[..input.len() - 4].to_vec();
+        assert!(parse_input(&missing).is_err());
+    }
+
+    #[test]
+    fn test_parse_three_tables() {
+        let input = [
+            "seeds: 79 14 55 13",
+            "",
+            "seed-to-soil map:",
+            "50 98 2",
+            "52 50 48",
+            "",
+            "soil-to-water map:",
+            "0 15 37",
+            "37 52 2",
+            "39 0 15",
+            "",
+            "water-to-location map:",
+            "88 18 7",
+            "18 25 70",
+        ]
+        .map(|l| owned!(l));
+
+        let (seeds, translation) = parse_input(&input).expect("Parsing should work");
+        assert_eq!(3, translation.translation.tables().len());
+        assert_eq!(Location(74), translation.transform(&Seed(79)));
+        assert_eq!(Location(46), translation.transform(&Seed(14)));
+        assert_eq!(4, seeds.len());
+
+        let dangling = input[..input.len() - 4].to_vec();
+        assert!(parse_input(&dangling).is_err());
+    }
+
+    #[test]
+    fn test_parse_duplicate_table() {
+        let input = [
+            "seeds: 79 14",
+            "",
+            "seed-to-location map:",
+            "50 98 2",
+            "",
+            "seed-to-location map:",
+            "0 15 37",
+        ]
+        .map(|l| owned!(l));
+
+        assert!(matches!(
+            parse_input(&input),
+            Err(SolutionError::InputParsingFailed(_))
+        ));
+        assert!(parse_input(&input[..5]).is_ok());
+    }
+
+    #[test]
+    fn test_location_histogram() {
+        let input = include_str!("../input/sample.txt")
+            .lines()
+            .map(|l| owned!(l))
+            .collect::<Vec<_>>();
+        let (seeds, translation) = parse_input(&input).expect("Parsing should work");
+        let histogram = location_histogram(&seeds, &translation).expect("Locations should fit");
+        let Location(lowest) = part_two(seeds, translation).expect("Solution should be found");
+
+        assert!(histogram
+            .iter()
+            .any(|&((start, end), count)| lowest >= start && lowest <= end && count > 0));
+        assert!(histogram.iter().map(|(_, count)| count).sum::<u64>() <= 14 + 13);
+
+        let translation = Translation::new(vec![TranslationRule::new(0, 5, -1)]).typed();
+        assert!(location_histogram(&[Seed(0), Seed(3)], &translation).is_err());
+    }
+
+    #[test]
+    fn test_validate_chain() {
+        let seed2soil = Translation::new(vec![
+            "50 98 2".parse().unwrap(),
+            "52 50 48".parse().unwrap(),
+        ]);
+        let soil2fertilizer = Translation::new(vec!["0 15 37".parse().unwrap()]);
+        assert!(seed2soil.and_then(soil2fertilizer).validate_chain().is_ok());
+
+        let seed2soil = Translation::new(vec!["50 98 2".parse().unwrap()]);
+        let soil2fertilizer = Translation::new(vec![]);
+        let fertilizer2water = Translation::new(vec!["49 53 8".parse().unwrap()]);
+        let chain = seed2soil
+            .and_then(soil2fertilizer)
+            .and_then(fertilizer2water);
+        assert!(chain.validate_chain().is_err());
+    }
+
+    #[test]
+    fn test_stages() {
+        let seed2soil = Translation::new(vec![
+            "50 98 2".parse().unwrap(),
+            "52 50 48".parse().unwrap(),
+        ]);
+        let soil2fertilizer = Translation::new(vec![
+            "0 15 37".parse().unwrap(),
+            "37 52 2".parse().unwrap(),
+            "39 0 15".parse().unwrap(),
+        ]);
+        let translation = seed2soil.and_then(soil2fertilizer);
+
+        assert_eq!(vec![79, 81, 81], translation.stages(79).collect::<Vec<_>>());
+        assert_eq!(vec![14, 14, 53], translation.stages(14).collect::<Vec<_>>());
+        assert_eq!(
+            translation.translate(&55),
+            translation.stages(55).last().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_transform_range() {
+        let seed2soil = Translation::new(vec![
+            "50 98 2".parse().unwrap(),
+            "52 50 48".parse().unwrap(),
+        ]);
+        let soil2fertilizer = Translation::new(vec![
+            "0 15 37".parse().unwrap(),
+            "37 52 2".parse().unwrap(),
+            "39 0 15".parse().unwrap(),
+        ]);
+        let translation = seed2soil
+            .typed::<Seed, Soil>()
+            .and_then(soil2fertilizer.typed::<Soil, Fertilizer>());
+
+        let ranges = translation.transform_range((45, 55));
+        assert_eq!(vec![(30, 34), (37, 38), (54, 57)], ranges);
+
+        assert!((45..=55).all(|seed| {
+            let fertilizer = *translation.transform(&Seed(seed));
+            ranges
+                .iter()
+                .any(|&(start, end)| fertilizer >= start && fertilizer <= end)
+        }));
+    }
+
+    #[test]
+    fn test_reverse() {
+        let forward = || {
+            let seed2soil = Translation::new(vec![
+                "50 98 2".parse().unwrap(),
+                "52 50 48".parse().unwrap(),
+            ]);
+            let soil2fertilizer = Translation::new(vec![
+                "0 15 37".parse().unwrap(),
+                "37 52 2".parse().unwrap(),
+                "39 0 15".parse().unwrap(),
+            ]);
+            seed2soil
+                .typed::<Seed, Soil>()
+                .and_then(soil2fertilizer.typed::<Soil, Fertilizer>())
+        };
+
+        let fertilizer = forward().transform(&Seed(79));
+        assert_eq!(81, *fertilizer);
+
+        let backward = forward().reverse().expect("Sample tables are invertible");
+        assert_eq!(79, *backward.transform(&fertilizer));
+        assert!(
+            (0..150).all(|seed| { *backward.transform(&forward().transform(&Seed(seed))) == seed })
+        );
+
+        let overlapping = TranslationTable::new(vec![
+            TranslationRule::new(0, 4, 10),
+            TranslationRule::new(5, 9, 5),
+        ]);
+        assert!(overlapping.reverse().is_err());
+
+        let into_gap = TranslationTable::new(vec![TranslationRule::new(0, 4, 10)]);
+        assert!(into_gap.reverse().is_err());
+
+        let swap = TranslationTable::new(vec![
+            TranslationRule::new(0, 4, 10),
+            TranslationRule::new(10, 14, -10),
+        ]);
+        assert_eq!(
+            swap.0.iter().cloned().collect::<Vec<_>>(),
+            swap.reverse()
+                .expect("Swapping ranges is invertible")
+                .0
+                .iter()
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_simplify() {
+        let input = include_str!("../input/sample.txt")
+            .lines()
+            .map(|l| owned!(l))
+            .collect::<Vec<_>>();
+        let (_, translation) = parse_input(&input).expect("Parsing should work");
+        let (_, simplified) = parse_input(&input).expect("Parsing should work");
+        let simplified = simplified.simplify();
+
+        assert!(matches!(simplified.translation, Translation::Table(_)));
+        assert!((0..200).all(|seed| {
+            translation.transform(&Seed(seed)) == simplified.transform(&Seed(seed))
+        }));
+    }
+
+    #[test]
+    fn test_seed_for_location() {
+        let input = include_str!("../input/sample.txt")
+            .lines()
+            .map(|l| owned!(l))
+            .collect::<Vec<_>>();
+        let (seeds, translation) = parse_input(&input).expect("Parsing should work");
+
+        let seed = seed_for_location(46, &translation, &seeds).expect("Seed should be found");
+        assert_eq!(82, *seed);
+        assert_eq!(Location(46), translation.transform(&seed));
+
+        assert!(seed_for_location(35, &translation, &seeds).is_none());
     }
 
     #[test]
@@ -711,4 +1378,45 @@ mod test {
         // let result = Translation::merge(seed2soil, soil2fertilizer);
         // assert_eq!(expected, result);
     }
+
+    fn chain(tables: &[Vec<(u64, u64, u64)>]) -> Translation {
+        tables
+            .iter()
+            .map(|rules| {
+                let (_, rules) = rules.iter().fold(
+                    (0, vec![]),
+                    |(offset, mut rules), &(gap, length, destination)| {
+                        let start = offset + gap;
+                        let end = start + length - 1;
+                        rules.push(TranslationRule::new(
+                            start,
+                            end,
+                            destination as i64 - start as i64,
+                        ));
+                        (end + 1, rules)
+                    },
+                );
+                Translation::new(rules)
+            })
+            .reduce(Translation::and_then)
+            .expect("At least one table")
+    }
+
+    proptest! {
+        #[test]
+        fn test_transform_matches_collapsed_table(
+            tables in prop::collection::vec(
+                prop::collection::vec((0u64..20, 1u64..20, 0u64..200), 0..5),
+                1..5
+            ),
+            inputs in prop::collection::vec(0u64..300, 1..20)
+        ) {
+            let translation = chain(&tables).typed::<Seed, Location>();
+            let collapsed = chain(&tables).collapse_table();
+
+            for x in inputs {
+                prop_assert_eq!(*translation.transform(&Seed(x)), collapsed.translate(&x));
+            }
+        }
+    }
 }
@@ -5,37 +5,46 @@ use utils::prelude::*;
 fn main() {
     setup_logging();
 
-    let input: Vec<String> = read_input_lines().expect("Input could not be read");
+    let input = read_exactly(2).expect("Input could not be read");
     let (times, distances) = parse_input(&input).expect("Input could not be parsed");
 
     let part_one = part_one(&times, &distances);
-    show_part_one(part_one);
+    show_result_part_one(part_one);
 
     let part_two = part_two(&times, &distances);
-    show_part_one(part_two);
+    show_result_part_two(part_two);
 }
 
-fn part_one(times: &[u32], distances: &[u32]) -> u64 {
+fn part_one(times: &[u32], distances: &[u32]) -> SolutionResult<u64> {
     times
         .iter()
         .zip(distances.iter())
-        .map(|(&time, &distance)| {
-            let (a, b) = calculate_range(time as u64, distance as u64);
-            b - a + 1
-        })
+        .map(|(&time, &distance)| ways_for_combined(time as u64, distance as u64))
         .product()
 }
 
-fn part_two(times: &[u32], distances: &[u32]) -> u64 {
-    let total_time = times.iter().fold(0, |total, time| {
-        *time as u64 + total * 10u64.pow((*time as f32).log10().ceil() as u32)
-    });
-    let total_distance = distances.iter().fold(0, |total, distance| {
-        *distance as u64 + total * 10u64.pow((*distance as f32).log10().ceil() as u32)
-    });
+fn part_two(times: &[u32], distances: &[u32]) -> SolutionResult<u64> {
+    ways_for_combined(concat_numbers(times)?, concat_numbers(distances)?)
+}
+
+fn concat_numbers(parts: &[u32]) -> SolutionResult<u64> {
+    parts.iter().try_fold(0u64, |total, &part| {
+        let digits = part.checked_ilog10().map_or(1, |d| d + 1);
+        total
+            .checked_mul(10u64.pow(digits))
+            .and_then(|total| total.checked_add(part as u64))
+            .ok_or_else(|| SolutionError::overflow("concatenating race numbers"))
+    })
+}
+
+fn ways_for_combined(time: u64, distance: u64) -> SolutionResult<u64> {
+    winning_range(time, distance)
+        .map(|(a, b)| b - a + 1)
+        .ok_or(SolutionError::NoSolutionFound)
+}
 
-    let (a, b) = calculate_range(total_time, total_distance);
-    b - a + 1
+fn winning_range(time: u64, record: u64) -> Option<(u64, u64)> {
+    calculate_range(time, record).ok()
 }
 
 fn parse_input(lines: &[String]) -> SolutionResult<(Vec<u32>, Vec<u32>)> {
@@ -51,33 +60,164 @@ fn parse_input(lines: &[String]) -> SolutionResult<(Vec<u32>, Vec<u32>)> {
         .ok_or_else(|| {
             SolutionError::InputParsingFailed("Could not extract exactl y 2 lines".to_owned())
         })?;
-    Ok((times?, distances?))
+    let (times, distances): (Vec<u32>, Vec<u32>) = (times?, distances?);
+
+    if times.len() != distances.len() {
+        return Err(SolutionError::InputParsingFailed(format!(
+            "Found {} times but {} distances",
+            times.len(),
+            distances.len()
+        )));
+    }
+
+    if let Some(index) = times.iter().position(|&time| time == 0) {
+        return Err(SolutionError::InputParsingFailed(format!(
+            "Race {} has no time",
+            index + 1
+        )));
+    }
+
+    Ok((times, distances))
+}
+
+#[cfg(test)]
+fn winning_holds(time: u64, distance: u64) -> impl Iterator<Item = u64> {
+    winning_range(time, distance)
+        .into_iter()
+        .flat_map(|(a, b)| a..=b)
+}
+
+fn race_quadratic(time: u64, distance: u64) -> (f64, f64, f64) {
+    (-1f64, time as f64, -(distance as f64))
 }
 
-fn calculate_range(total_time: u64, distance: u64) -> (u64, u64) {
-    let t: f64 = total_time as f64;
-    let d: f64 = distance as f64;
+fn calculate_range(total_time: u64, distance: u64) -> SolutionResult<(u64, u64)> {
+    let (a, b, c) = race_quadratic(total_time, distance);
+    let discriminant = b * b - 4f64 * a * c;
+    if discriminant < 0f64 {
+        return Err(SolutionError::NoSolutionFound);
+    }
+
+    let d1 = (-b + discriminant.sqrt()) / (2f64 * a);
+    let d2 = (-b - discriminant.sqrt()) / (2f64 * a);
 
-    let d1 = (t - (t * t - 4f64 * d).sqrt()) / 2f64;
-    let d2 = (t + (t * t - 4f64 * d).sqrt()) / 2f64;
+    let d1 = d1.next_after(f64::MAX).ceil() as u64;
+    let d2 = d2.next_after(f64::MIN).floor() as u64;
 
-    let d1 = d1.next_after(std::f64::MAX).ceil() as u64;
-    let d2 = d2.next_after(std::f64::MIN).floor() as u64;
+    // When the best hold only ties the record, both roots coincide and get nudged apart
+    if d1 > d2 {
+        return Err(SolutionError::NoSolutionFound);
+    }
 
-    (d1, d2)
+    Ok((d1, d2))
 }
 
 #[cfg(test)]
 mod test {
-    use utils::owned;
+    use itertools::Itertools;
+    use utils::{owned, result::SolutionError, testing::assert_close};
 
-    use crate::{calculate_range, parse_input};
+    use crate::{
+        calculate_range, concat_numbers, parse_input, part_one, part_two, race_quadratic,
+        ways_for_combined, winning_holds, winning_range,
+    };
 
     #[test]
     fn test_range_calculation() {
-        assert_eq!((2, 5), calculate_range(7, 9));
-        assert_eq!((4, 11), calculate_range(15, 40));
-        assert_eq!((11, 19), calculate_range(30, 200));
+        assert_eq!((2, 5), calculate_range(7, 9).unwrap());
+        assert_eq!((4, 11), calculate_range(15, 40).unwrap());
+        assert_eq!((11, 19), calculate_range(30, 200).unwrap());
+    }
+
+    #[test]
+    fn test_unbeatable_record() {
+        assert!(matches!(
+            calculate_range(3, 10),
+            Err(SolutionError::NoSolutionFound)
+        ));
+        assert!(calculate_range(4, 4).is_err());
+        assert_eq!((2, 2), calculate_range(4, 3).unwrap());
+
+        assert!(part_one(&[7, 3], &[9, 10]).is_err());
+        assert!(part_two(&[3], &[10]).is_err());
+    }
+
+    #[test]
+    fn test_concat_numbers() {
+        assert_eq!(71530, concat_numbers(&[7, 15, 30]).unwrap());
+        assert_eq!(9100, concat_numbers(&[9, 100]).unwrap());
+        assert_eq!(1000100, concat_numbers(&[1000, 100]).unwrap());
+        assert_eq!(1001000, concat_numbers(&[100, 1000]).unwrap());
+        assert_eq!(500, concat_numbers(&[5, 0, 0]).unwrap());
+        assert_eq!(0, concat_numbers(&[]).unwrap());
+        assert!(matches!(
+            concat_numbers(&[u32::MAX; 3]),
+            Err(SolutionError::Overflow(_))
+        ));
+        assert!(part_two(&[u32::MAX; 3], &[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_winning_range() {
+        assert_eq!(Some((2, 5)), winning_range(7, 9));
+        assert_eq!(Some((11, 19)), winning_range(30, 200));
+        assert_eq!(None, winning_range(3, 10));
+        assert_eq!(None, winning_range(4, 4));
+        assert_eq!(Some((2, 2)), winning_range(4, 3));
+    }
+
+    #[test]
+    fn test_ways_for_combined() {
+        assert_eq!(4, ways_for_combined(7, 9).unwrap());
+        assert_eq!(71503, ways_for_combined(71530, 940200).unwrap());
+    }
+
+    #[test]
+    fn test_parts_share_core() {
+        let input = include_str!("../input/sample.txt")
+            .lines()
+            .map(|l| owned!(l))
+            .collect_vec();
+        let (times, distances) = parse_input(&input).expect("Parsing input failed");
+
+        let per_race: u64 = times
+            .iter()
+            .zip(distances.iter())
+            .map(|(&t, &d)| ways_for_combined(t as u64, d as u64).unwrap())
+            .product();
+        let combined = ways_for_combined(
+            concat_numbers(&times).unwrap(),
+            concat_numbers(&distances).unwrap(),
+        )
+        .unwrap();
+        let (one, two) = (
+            part_one(&times, &distances).unwrap(),
+            part_two(&times, &distances).unwrap(),
+        );
+
+        assert!(
+            (one, two) == (288, 71503) && (one, two) == (per_race, combined),
+            "part one {} (core {}) and part two {} (core {}) diverged",
+            one,
+            per_race,
+            two,
+            combined
+        );
+    }
+
+    #[test]
+    fn test_winning_holds() {
+        assert_eq!(vec![2, 3, 4, 5], winning_holds(7, 9).collect_vec());
+        assert_eq!(9, winning_holds(30, 200).count());
+        assert_eq!(0, winning_holds(3, 10).count());
+    }
+
+    #[test]
+    fn test_race_quadratic() {
+        let (a, b, c) = race_quadratic(7, 9);
+        assert_close(-1f64, a, 0);
+        assert_close(7f64, b, 0);
+        assert_close(-9f64, c, 0);
     }
 
     #[test]
@@ -91,4 +231,16 @@ mod test {
         assert_eq!(vec![7, 15, 30], times);
         assert_eq!(vec![9, 40, 200], distances);
     }
+
+    #[test]
+    fn test_input_validation() {
+        let input = vec![owned!("Time:      7  15   30"), owned!("Distance:  9  40")];
+        assert!(parse_input(&input).is_err());
+
+        let input = vec![
+            owned!("Time:      7  0   30"),
+            owned!("Distance:  9  40  200"),
+        ];
+        assert!(parse_input(&input).is_err());
+    }
 }
@@ -1,4 +1,6 @@
 use itertools::Itertools;
+#[cfg(test)]
+use std::collections::HashMap;
 use std::convert::TryInto;
 use std::str::FromStr;
 use utils::prelude::*;
@@ -7,11 +9,39 @@ fn main() {
     setup_logging();
 
     let bids: Vec<HandWithBid> = parse_input_lines().expect("Input could not be parsed");
-    let part_one = part_one(&bids);
+    validate_hands(&bids).expect("Input contains invalid hands");
+
+    let (part_one, part_two) = solve_both(bids);
     show_part_one(part_one);
+    show_part_two(part_two);
+}
 
-    let part_two = part_two(bids);
-    show_part_one(part_two);
+fn solve_both(bids: Vec<HandWithBid>) -> (u64, u64) {
+    let standard = part_one(&bids);
+    (standard, part_two(bids))
+}
+
+fn validate_hands(bids: &[HandWithBid]) -> SolutionResult<()> {
+    bids.iter()
+        .enumerate()
+        .try_for_each(|(pos, HandWithBid(hand, _))| {
+            if hand.cards.contains(&Card::Joker) {
+                Err(SolutionError::InputParsingFailed(format!(
+                    "Hand {} contains a joker: {:?}",
+                    pos + 1,
+                    hand.cards
+                )))
+            } else if hand.hand_type != HandType::from(hand.cards) {
+                Err(SolutionError::InputParsingFailed(format!(
+                    "Hand {} is not a {:?}: {:?}",
+                    pos + 1,
+                    hand.hand_type,
+                    hand.cards
+                )))
+            } else {
+                Ok(())
+            }
+        })
 }
 
 fn part_one(bids: &[HandWithBid]) -> u64 {
@@ -49,11 +79,57 @@ enum Card {
     Ace,
 }
 
+#[cfg(test)]
+impl Card {
+    const ALL: [Card; 14] = [
+        Card::Joker,
+        Card::Two,
+        Card::Three,
+        Card::Four,
+        Card::Five,
+        Card::Six,
+        Card::Seven,
+        Card::Eight,
+        Card::Nine,
+        Card::Ten,
+        Card::Jack,
+        Card::Queen,
+        Card::King,
+        Card::Ace,
+    ];
+}
+
+/// A rank for every card, so hands can be compared under orderings other than the derived one.
+#[cfg(test)]
+struct Ranks(HashMap<Card, u8>);
+
+#[cfg(test)]
+impl Ranks {
+    fn new(ranks: HashMap<Card, u8>) -> SolutionResult<Self> {
+        match Card::ALL.iter().find(|card| !ranks.contains_key(card)) {
+            Some(card) => Err(SolutionError::InputParsingFailed(format!(
+                "No rank given for {:?}",
+                card
+            ))),
+            None => Ok(Ranks(ranks)),
+        }
+    }
+
+    fn standard() -> Self {
+        Ranks::new(Card::ALL.iter().map(|&card| (card, card as u8)).collect())
+            .expect("Every card ranks as itself")
+    }
+
+    fn rank(&self, card: Card) -> u8 {
+        self.0[&card]
+    }
+}
+
 impl TryFrom<char> for Card {
     type Error = SolutionError;
 
     fn try_from(value: char) -> Result<Self, Self::Error> {
-        match value {
+        match value.to_ascii_uppercase() {
             'A' => Ok(Self::Ace),
             '2' => Ok(Self::Two),
             '3' => Ok(Self::Three),
@@ -67,7 +143,7 @@ impl TryFrom<char> for Card {
             'J' => Ok(Self::Jack),
             'Q' => Ok(Self::Queen),
             'K' => Ok(Self::King),
-            value => Err(SolutionError::InputParsingFailed(format!(
+            _ => Err(SolutionError::InputParsingFailed(format!(
                 "Could not parse '{}'",
                 value
             ))),
@@ -75,7 +151,7 @@ impl TryFrom<char> for Card {
     }
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Copy, Clone, Hash)]
 enum HandType {
     HighCard,
     OnePair,
@@ -143,6 +219,11 @@ impl Hand {
         Ok(Self::new(cards))
     }
 
+    #[cfg(test)]
+    fn with_ranks(&self, ranks: &Ranks) -> (HandType, [u8; 5]) {
+        (self.hand_type, self.cards.map(|card| ranks.rank(card)))
+    }
+
     fn replace_jack_with_joker(self) -> Self {
         let updated_cards = self
             .cards
@@ -250,6 +331,54 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_lowercase_hand_parsing() {
+        let lower: Hand = "akqjt".parse().expect("Parsing should work");
+        let upper: Hand = "AKQJT".parse().expect("Parsing should work");
+        assert_eq!(upper, lower);
+
+        let mixed: Hand = "t55j5".parse().expect("Parsing should work");
+        assert_eq!(HandType::ThreeOfAKind, mixed.hand_type);
+        assert!("x55j5".parse::<Hand>().is_err());
+    }
+
+    #[test]
+    fn test_validate_hands() {
+        let bids: Vec<HandWithBid> = vec![
+            "32T3K 765".parse().expect("Parsing should work"),
+            "T55J5 684".parse().expect("Parsing should work"),
+        ];
+        assert!(validate_hands(&bids).is_ok());
+
+        let joker = HandWithBid(
+            Hand::new([Card::Joker, Card::Two, Card::Three, Card::Four, Card::Five]),
+            1,
+        );
+        assert!(validate_hands(&[joker]).is_err());
+
+        let mislabeled = HandWithBid(
+            Hand {
+                cards: [Card::Two, Card::Two, Card::Three, Card::Four, Card::Five],
+                hand_type: HandType::FiveOfAKind,
+            },
+            1,
+        );
+        assert!(validate_hands(&[mislabeled]).is_err());
+    }
+
+    #[test]
+    fn test_hand_type_counts() {
+        let counts: HashMap<HandType, usize> = ["32T3K", "T55J5", "KK677", "KTJJT", "QQQJA"]
+            .iter()
+            .map(|h| h.parse::<Hand>().expect("Parsing should work").hand_type)
+            .counts();
+
+        assert_eq!(Some(&1), counts.get(&HandType::OnePair));
+        assert_eq!(Some(&2), counts.get(&HandType::TwoPair));
+        assert_eq!(Some(&2), counts.get(&HandType::ThreeOfAKind));
+        assert_eq!(None, counts.get(&HandType::FiveOfAKind));
+    }
+
     #[test]
     fn test_hand_sorting() {
         let hands: [Hand; 5] = [
@@ -328,6 +457,46 @@ mod test {
         assert!(d > c);
     }
 
+    #[test]
+    fn test_hand_with_ranks() {
+        let a: Hand = "A2222".parse().expect("Parsing should work");
+        let k: Hand = "K2222".parse().expect("Parsing should work");
+        assert!(a > k);
+
+        let ace_low: HashMap<Card, u8> = [
+            Card::Joker,
+            Card::Ace,
+            Card::Two,
+            Card::Three,
+            Card::Four,
+            Card::Five,
+            Card::Six,
+            Card::Seven,
+            Card::Eight,
+            Card::Nine,
+            Card::Ten,
+            Card::Jack,
+            Card::Queen,
+            Card::King,
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(rank, card)| (card, rank as u8))
+        .collect();
+        let ace_low = Ranks::new(ace_low).expect("All cards are ranked");
+
+        assert!(a.with_ranks(&ace_low) < k.with_ranks(&ace_low));
+
+        let pair: Hand = "AA234".parse().expect("Parsing should work");
+        assert!(pair.with_ranks(&ace_low) < k.with_ranks(&ace_low));
+
+        let standard = Ranks::standard();
+        assert!(a.with_ranks(&standard) > k.with_ranks(&standard));
+
+        let incomplete = HashMap::from([(Card::Ace, 1), (Card::King, 13)]);
+        assert!(Ranks::new(incomplete).is_err());
+    }
+
     #[test]
     fn test_joker_parsing() {
         let hand: Hand = "A2345"
@@ -436,4 +605,15 @@ mod test {
             hand.cards
         );
     }
+
+    #[test]
+    fn test_solve_both() {
+        let bids: Vec<HandWithBid> = include_str!("../input/sample.txt")
+            .lines()
+            .map(|l| l.parse())
+            .try_collect()
+            .expect("Sample could not be parsed");
+
+        assert_eq!((6440, 5905), solve_both(bids));
+    }
 }
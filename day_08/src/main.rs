@@ -1,7 +1,6 @@
-use std::{
-    collections::{HashMap, HashSet},
-    str::FromStr,
-};
+#[cfg(test)]
+use std::collections::HashSet;
+use std::{collections::HashMap, str::FromStr};
 
 use derive_more::From;
 use itertools::{FoldWhile, Itertools};
@@ -18,7 +17,7 @@ fn main() {
     let part_one = part_one(&instructions, &network);
     show_result_part_one(part_one);
 
-    let part_two = part_two(&instructions, &network);
+    let part_two = part_two(&instructions, &network, false);
     show_result_part_two(part_two);
 }
 
@@ -28,14 +27,19 @@ where
 {
     let mut iter = input.into_iter();
 
-    let instruction_line: String = iter
-        .next()
-        .ok_or_else(|| SolutionError::InputParsingFailed(owned!("No instruction line in input")))?;
-    let instructions: Vec<Instruction> = instruction_line
-        .chars()
+    let instructions: Vec<Instruction> = iter
+        .by_ref()
+        .take_while(|l| !l.is_empty())
+        .flat_map(|l| l.chars().collect_vec())
         .map(|c| c.try_into())
         .try_collect()?;
 
+    if instructions.is_empty() {
+        return Err(SolutionError::InputParsingFailed(owned!(
+            "No instruction line in input"
+        )));
+    }
+
     let nodes: Vec<NodeDefinition> = iter
         .filter(|l| !l.is_empty())
         .map(|l| l.parse::<NodeDefinition>())
@@ -45,8 +49,15 @@ where
 }
 
 fn part_one(instructions: &[Instruction], network: &Network) -> Result<usize, SolutionError> {
+    let start = network
+        .start()
+        .ok_or_else(|| SolutionError::InputParsingFailed(owned!("No AAA start node")))?;
+    if network.end().is_none() {
+        return Err(SolutionError::InputParsingFailed(owned!("No ZZZ end node")));
+    }
+
     let result = instructions.iter().cycle().fold_while(
-        (0, network.start()),
+        (0, Some(start)),
         |(length, position), instruction| match position {
             Some(node) if node.is_end() => {
                 tracing::debug!(length = length, node = node.0, "Found the end");
@@ -74,7 +85,67 @@ fn part_one(instructions: &[Instruction], network: &Network) -> Result<usize, So
     }
 }
 
-fn part_two(instructions: &[Instruction], network: &Network) -> Result<u128, SolutionError> {
+fn part_two(
+    instructions: &[Instruction],
+    network: &Network,
+    first_z: bool,
+) -> Result<u128, SolutionError> {
+    let cycle_lengths: Vec<usize> = if first_z {
+        first_z_lengths(instructions, network)?
+            .into_iter()
+            .map(|(_, length)| length)
+            .collect()
+    } else {
+        detected_cycles(instructions, network)?
+            .into_iter()
+            .map(|(_, length)| length)
+            .collect()
+    };
+
+    Ok(cycle_lengths
+        .into_iter()
+        .fold(1u128, |a, b| num::integer::lcm(a, b as u128)))
+}
+
+fn first_z_lengths<'a>(
+    instructions: &[Instruction],
+    network: &'a Network,
+) -> Result<Vec<(&'a Node, usize)>, SolutionError> {
+    network
+        .ghost_start::<Vec<_>>()
+        .into_iter()
+        .map(|start| {
+            let mut position = start;
+            for (length, instruction) in instructions.iter().cycle().enumerate() {
+                if position.is_ghost_end() {
+                    tracing::debug!(length, node = start.0, "Found first end");
+                    return Ok((start, length));
+                }
+                position = network
+                    .lookup(position, instruction)
+                    .ok_or(SolutionError::NoSolutionFound)?;
+            }
+            Err(SolutionError::NoSolutionFound)
+        })
+        .try_collect()
+}
+
+#[cfg(test)]
+fn part_two_with_trace(
+    instructions: &[Instruction],
+    network: &Network,
+) -> SolutionResult<(u128, Vec<(String, usize)>)> {
+    let trace = detected_cycles(instructions, network)?;
+    let answer = trace
+        .iter()
+        .fold(1u128, |a, (_, b)| num::integer::lcm(a, *b as u128));
+    Ok((answer, trace))
+}
+
+fn detected_cycles(
+    instructions: &[Instruction],
+    network: &Network,
+) -> Result<Vec<(String, usize)>, SolutionError> {
     let starts: Vec<_> = network.ghost_start();
 
     tracing::info!("Need to resolve {} paths", starts.len());
@@ -173,20 +244,155 @@ fn part_two(instructions: &[Instruction], network: &Network) -> Result<u128, Sol
 
     match result {
         FoldWhile::Done(Ok(outcome)) => {
-            let cycle_lengths: HashSet<usize> = outcome
-                .values()
-                .map(|(_, _, c)| c.ok_or(SolutionError::NoSolutionFound))
+            let cycles: Vec<(String, usize)> = outcome
+                .into_iter()
+                .map(|(prefix, (_, _, c))| {
+                    c.map(|c| (prefix.to_owned(), c))
+                        .ok_or(SolutionError::NoSolutionFound)
+                })
                 .try_collect()?;
-            Ok(cycle_lengths
+            Ok(cycles
                 .into_iter()
-                .fold(1u128, |a, b| num::integer::lcm(a, b as u128)))
+                .sorted_by_key(|(prefix, cycle)| (*cycle, prefix.clone()))
+                .collect())
         }
         FoldWhile::Continue(Err(e)) => Err(e),
         _ => unreachable!("Iteration never stops"),
     }
 }
 
-#[derive(Debug)]
+#[cfg(test)]
+fn find_cycle(
+    instructions: &[Instruction],
+    network: &Network,
+    start: &Node,
+) -> Result<(usize, usize, Vec<usize>), SolutionError> {
+    if instructions.is_empty() {
+        return Err(SolutionError::NoSolutionFound);
+    }
+
+    let mut seen: HashMap<(&Node, usize), usize> = HashMap::new();
+    let mut ends = vec![];
+    let mut position = start;
+    let mut step = 0;
+
+    loop {
+        let phase = step % instructions.len();
+        if let Some(&cycle_start) = seen.get(&(position, phase)) {
+            let period = step - cycle_start;
+            let offset = *ends.first().ok_or(SolutionError::NoSolutionFound)?;
+            let cycle_ends = ends
+                .into_iter()
+                .filter(|&end| end >= cycle_start)
+                .collect_vec();
+
+            if cycle_ends.is_empty() {
+                return Err(SolutionError::NoSolutionFound);
+            }
+
+            tracing::debug!(
+                node = start.0,
+                offset,
+                period,
+                "Cycle detected with ends at {:?}",
+                cycle_ends
+            );
+            return Ok((offset, period, cycle_ends));
+        }
+
+        seen.insert((position, phase), step);
+        if position.is_ghost_end() {
+            ends.push(step);
+        }
+
+        position = network
+            .lookup(position, &instructions[phase])
+            .ok_or(SolutionError::NoSolutionFound)?;
+        step += 1;
+    }
+}
+
+#[cfg(test)]
+fn ghost_cycle_lengths<'a>(
+    instructions: &[Instruction],
+    network: &'a Network,
+) -> Result<Vec<(&'a Node, usize)>, SolutionError> {
+    network
+        .ghost_start::<Vec<_>>()
+        .into_iter()
+        .map(|start| {
+            let (_, period, ends) = find_cycle(instructions, network, start)?;
+            let gaps: HashSet<usize> = ends
+                .iter()
+                .chain(ends.first().map(|first| first + period).iter())
+                .tuple_windows()
+                .map(|(a, b)| b - a)
+                .collect();
+
+            match gaps.into_iter().collect_tuple() {
+                Some((cycle,)) => Ok((start, cycle)),
+                _ => Err(SolutionError::NoSolutionFound),
+            }
+        })
+        .try_collect()
+}
+
+#[cfg(test)]
+fn distinct_ends<'a>(
+    instructions: &[Instruction],
+    network: &'a Network,
+    max_steps: usize,
+) -> SolutionResult<HashSet<&'a Node>> {
+    network
+        .ghost_start::<Vec<_>>()
+        .into_iter()
+        .try_fold(HashSet::new(), |mut ends, start| {
+            let mut position = start;
+            for instruction in instructions.iter().cycle().take(max_steps) {
+                if position.is_ghost_end() {
+                    ends.insert(position);
+                }
+                position = network
+                    .lookup(position, instruction)
+                    .ok_or(SolutionError::NoSolutionFound)?;
+            }
+            if position.is_ghost_end() {
+                ends.insert(position);
+            }
+            Ok(ends)
+        })
+}
+
+#[cfg(test)]
+fn phase_at_end(
+    instructions: &[Instruction],
+    network: &Network,
+    start: &Node,
+) -> Result<usize, SolutionError> {
+    if instructions.is_empty() {
+        return Err(SolutionError::NoSolutionFound);
+    }
+
+    // Walk the path itself rather than its cycle, so an end in the lead-in is found as well
+    let mut seen = HashSet::new();
+    let mut position = start;
+    for step in 0.. {
+        let phase = step % instructions.len();
+        if position.is_ghost_end() {
+            return Ok(phase);
+        }
+        if !seen.insert((position, phase)) {
+            break;
+        }
+        position = network
+            .lookup(position, &instructions[phase])
+            .ok_or(SolutionError::NoSolutionFound)?;
+    }
+
+    Err(SolutionError::NoSolutionFound)
+}
+
+#[derive(Debug, PartialEq, Eq)]
 enum Instruction {
     Left,
     Right,
@@ -215,7 +421,7 @@ impl TryFrom<char> for Instruction {
     }
 }
 
-#[derive(From, Debug, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[derive(From, Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
 struct Node(String);
 
 impl Node {
@@ -275,6 +481,7 @@ impl From<NodeDefinition> for (Node, (Node, Node)) {
     }
 }
 
+#[derive(Debug, Clone)]
 struct Network(HashMap<Node, (Node, Node)>);
 
 impl Network {
@@ -286,15 +493,33 @@ impl Network {
         Network(input.into_iter().map_into().collect())
     }
 
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[cfg(test)]
+    fn contains(&self, node: &Node) -> bool {
+        self.0.contains_key(node)
+    }
+
     fn start(&self) -> Option<&Node> {
         self.0.keys().find(|n| n.is_start())
     }
 
+    fn end(&self) -> Option<&Node> {
+        self.0.keys().find(|n| n.is_end())
+    }
+
     fn ghost_start<'a, I>(&'a self) -> I
     where
         I: FromIterator<&'a Node>,
     {
-        self.0.keys().filter(|n| n.is_ghost_start()).collect()
+        self.0
+            .keys()
+            .filter(|n| n.is_ghost_start())
+            .sorted()
+            .collect()
     }
 
     fn lookup(&self, node: &Node, instruction: &Instruction) -> Option<&Node> {
@@ -321,6 +546,159 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_parse_multiline_instructions() {
+        let input = vec![
+            owned!("LR"),
+            owned!("RL"),
+            owned!(""),
+            owned!("AAA = (BBB, BBB)"),
+            owned!("BBB = (AAA, ZZZ)"),
+            owned!("ZZZ = (ZZZ, ZZZ)"),
+        ];
+
+        let (instructions, network) = parse_input(input).expect("Parsing should work");
+        assert_eq!(
+            vec![
+                Instruction::Left,
+                Instruction::Right,
+                Instruction::Right,
+                Instruction::Left
+            ],
+            instructions
+        );
+        assert!(network.start().is_some());
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let input: Vec<NodeDefinition> = vec![
+            "11A = (11B, 11B)".parse().unwrap(),
+            "11B = (11C, 11C)".parse().unwrap(),
+            "11C = (11Z, 11Z)".parse().unwrap(),
+            "11Z = (11C, 11C)".parse().unwrap(),
+        ];
+        let network = Network::new(input);
+        let start: Node = "11A".into();
+
+        let (offset, period, ends) =
+            find_cycle(&[Instruction::Left], &network, &start).expect("Cycle should be found");
+        assert_eq!(3, offset);
+        assert_eq!(2, period);
+        assert_eq!(vec![3], ends);
+    }
+
+    #[test]
+    fn test_ghost_cycle_lengths() {
+        let input = include_str!("../input/sample3.txt")
+            .lines()
+            .map(|l| owned!(l));
+        let (instructions, network) = parse_input(input).expect("Parsing should work");
+
+        let a: Node = "11A".into();
+        let b: Node = "22A".into();
+        assert_eq!(
+            vec![(&a, 2), (&b, 3)],
+            ghost_cycle_lengths(&instructions, &network).expect("Cycles should be found")
+        );
+    }
+
+    #[test]
+    fn test_first_z_lengths() {
+        let input = include_str!("../input/sample3.txt")
+            .lines()
+            .map(|l| owned!(l));
+        let (instructions, network) = parse_input(input).expect("Parsing should work");
+
+        let a: Node = "11A".into();
+        let b: Node = "22A".into();
+        assert_eq!(
+            vec![(&a, 2), (&b, 3)],
+            first_z_lengths(&instructions, &network).expect("Ends should be found")
+        );
+
+        assert_eq!(
+            part_two(&instructions, &network, false).expect("Solution should be found"),
+            part_two(&instructions, &network, true).expect("Solution should be found")
+        );
+        assert_eq!(6, part_two(&instructions, &network, true).unwrap());
+    }
+
+    #[test]
+    fn test_part_two_with_trace() {
+        let input = include_str!("../input/sample3.txt")
+            .lines()
+            .map(|l| owned!(l));
+        let (instructions, network) = parse_input(input).expect("Parsing should work");
+
+        let (answer, trace) =
+            part_two_with_trace(&instructions, &network).expect("Solution should be found");
+        assert_eq!(6, answer);
+        assert_eq!(vec![(owned!("11"), 2), (owned!("22"), 3)], trace);
+        assert!(trace.iter().map(|(prefix, _)| prefix).all_unique());
+    }
+
+    #[test]
+    fn test_distinct_ends() {
+        let input = include_str!("../input/sample3.txt")
+            .lines()
+            .map(|l| owned!(l));
+        let (instructions, network) = parse_input(input).expect("Parsing should work");
+
+        let a: Node = "11Z".into();
+        let b: Node = "22Z".into();
+        assert_eq!(
+            HashSet::from([&a, &b]),
+            distinct_ends(&instructions, &network, 10).expect("Ends should be found")
+        );
+        assert_eq!(
+            HashSet::from([&a]),
+            distinct_ends(&instructions, &network, 2).expect("Ends should be found")
+        );
+        assert!(distinct_ends(&instructions, &network, 0)
+            .expect("Ends should be found")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_phase_at_end() {
+        let input: Vec<NodeDefinition> = vec![
+            "11A = (11Z, XXX)".parse().unwrap(),
+            "11Z = (11Z, 11Z)".parse().unwrap(),
+            "XXX = (XXX, XXX)".parse().unwrap(),
+        ];
+        let network = Network::new(input);
+        let start: Node = "11A".into();
+        let instructions = vec![Instruction::Left, Instruction::Right];
+
+        assert_eq!(
+            1,
+            phase_at_end(&instructions, &network, &start).expect("Phase should be found")
+        );
+    }
+
+    #[test]
+    fn test_phase_at_end_before_cycle() {
+        let input: Vec<NodeDefinition> = vec![
+            "11A = (11B, 11B)".parse().unwrap(),
+            "11B = (11Z, 11Z)".parse().unwrap(),
+            "11Z = (11C, 11C)".parse().unwrap(),
+            "11C = (11C, 11C)".parse().unwrap(),
+        ];
+        let network = Network::new(input);
+        let start: Node = "11A".into();
+        let instructions = vec![Instruction::Left, Instruction::Left, Instruction::Left];
+
+        assert!(find_cycle(&instructions, &network, &start).is_err());
+        assert_eq!(
+            2,
+            phase_at_end(&instructions, &network, &start).expect("Phase should be found")
+        );
+
+        let unreachable: Node = "11C".into();
+        assert!(phase_at_end(&instructions, &network, &unreachable).is_err());
+    }
+
     #[test]
     fn test_instruction() {
         let left: Instruction = 'L'.try_into().unwrap();
@@ -339,25 +717,25 @@ mod test {
         let ghost_start: Node = "BBA".into();
         let ghost_end: Node = "BBZ".into();
 
-        assert_eq!(start.is_start(), true);
-        assert_eq!(start.is_end(), false);
-        assert_eq!(start.is_ghost_start(), true);
-        assert_eq!(start.is_ghost_end(), false);
-
-        assert_eq!(end.is_start(), false);
-        assert_eq!(end.is_end(), true);
-        assert_eq!(end.is_ghost_start(), false);
-        assert_eq!(end.is_ghost_end(), true);
-
-        assert_eq!(ghost_start.is_start(), false);
-        assert_eq!(ghost_start.is_end(), false);
-        assert_eq!(ghost_start.is_ghost_start(), true);
-        assert_eq!(ghost_start.is_ghost_end(), false);
-
-        assert_eq!(ghost_end.is_start(), false);
-        assert_eq!(ghost_end.is_end(), false);
-        assert_eq!(ghost_end.is_ghost_start(), false);
-        assert_eq!(ghost_end.is_ghost_end(), true);
+        assert!(start.is_start());
+        assert!(!start.is_end());
+        assert!(start.is_ghost_start());
+        assert!(!start.is_ghost_end());
+
+        assert!(!end.is_start());
+        assert!(end.is_end());
+        assert!(!end.is_ghost_start());
+        assert!(end.is_ghost_end());
+
+        assert!(!ghost_start.is_start());
+        assert!(!ghost_start.is_end());
+        assert!(ghost_start.is_ghost_start());
+        assert!(!ghost_start.is_ghost_end());
+
+        assert!(!ghost_end.is_start());
+        assert!(!ghost_end.is_end());
+        assert!(!ghost_end.is_ghost_start());
+        assert!(ghost_end.is_ghost_end());
     }
 
     #[test]
@@ -380,6 +758,29 @@ mod test {
         assert_eq!(network.start().unwrap(), &start);
     }
 
+    #[test]
+    fn test_part_one_missing_nodes() {
+        let instructions = vec![Instruction::Left];
+
+        let no_start = Network::new(vec![
+            "BBB = (ZZZ, ZZZ)".parse::<NodeDefinition>().unwrap(),
+            "ZZZ = (ZZZ, ZZZ)".parse().unwrap(),
+        ]);
+        assert!(matches!(
+            part_one(&instructions, &no_start),
+            Err(SolutionError::InputParsingFailed(msg)) if msg == "No AAA start node"
+        ));
+
+        let no_end = Network::new(vec![
+            "AAA = (BBB, BBB)".parse::<NodeDefinition>().unwrap(),
+            "BBB = (AAA, AAA)".parse().unwrap(),
+        ]);
+        assert!(matches!(
+            part_one(&instructions, &no_end),
+            Err(SolutionError::InputParsingFailed(msg)) if msg == "No ZZZ end node"
+        ));
+    }
+
     #[test]
     fn test_network_ghost_start() {
         let input: Vec<NodeDefinition> = vec![
@@ -406,6 +807,25 @@ mod test {
         assert_eq!(ghost_starts, vec![&a, &b, &c]);
     }
 
+    #[test]
+    fn test_network_ghost_start_sorted() {
+        let input: Vec<NodeDefinition> = vec![
+            "CCA = (CCZ, CCZ)".parse().unwrap(),
+            "AAA = (AAZ, AAZ)".parse().unwrap(),
+            "DDA = (DDZ, DDZ)".parse().unwrap(),
+            "BBA = (BBZ, BBZ)".parse().unwrap(),
+        ];
+
+        let network = Network::new(input);
+        let ghost_starts: Vec<&str> = network
+            .ghost_start::<Vec<_>>()
+            .into_iter()
+            .map(|n| n.0.as_str())
+            .collect();
+
+        assert_eq!(vec!["AAA", "BBA", "CCA", "DDA"], ghost_starts);
+    }
+
     #[test]
     fn test_network_lookup() {
         let input: Vec<NodeDefinition> = vec![
@@ -433,4 +853,28 @@ mod test {
         assert_eq!(network.lookup(&z, &Instruction::Left), None);
         assert_eq!(network.lookup(&z, &Instruction::Right), None);
     }
+
+    #[test]
+    fn test_network_clone() {
+        let input: Vec<NodeDefinition> = vec![
+            "AAA = (BBB, CCC)".parse().unwrap(),
+            "BBB = (CCC, AAA)".parse().unwrap(),
+            "CCC = (AAA, ZZZ)".parse().unwrap(),
+        ];
+
+        let network = Network::new(input);
+        let clone = network.clone();
+        drop(network);
+
+        let a: Node = "AAA".into();
+        let c: Node = "CCC".into();
+        let z: Node = "ZZZ".into();
+
+        assert_eq!(3, clone.len());
+        assert!(clone.contains(&a));
+        assert!(!clone.contains(&z));
+        assert_eq!(clone.lookup(&a, &Instruction::Right), Some(&c));
+        assert_eq!(clone.lookup(&c, &Instruction::Right), Some(&z));
+        assert!(format!("{:?}", clone).contains("AAA"));
+    }
 }
@@ -31,7 +31,7 @@ fn part_two(input: &[Triangle]) -> Result<i64, SolutionError> {
         .fold_ok(0, |a, b| a + b as i64)
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Triangle {
     max_x: i32,
     min_x: i32,
@@ -40,35 +40,32 @@ struct Triangle {
 }
 
 impl Triangle {
-    fn from<I>(input: I) -> Triangle
+    fn from_values<I>(input: I) -> Triangle
     where
         I: IntoIterator<Item = i32>,
     {
-        fn extend(
-            y: usize,
-            row: Vec<i32>,
-            mut values: HashMap<(usize, i32), i32>,
-        ) -> (usize, HashMap<(usize, i32), i32>) {
+        let mut row: Vec<_> = input.into_iter().collect();
+        let max_x = row.len() as i32 - 1;
+        let mut values = HashMap::new();
+        let mut max_y = 0;
+
+        loop {
             let next_row: Vec<_> = row.iter().tuple_windows().map(|(a, b)| b - a).collect();
-            values.extend(row.iter().enumerate().map(|(x, i)| ((y, x as i32), *i)));
+            values.extend(row.iter().enumerate().map(|(x, i)| ((max_y, x as i32), *i)));
+            max_y += 1;
 
             if next_row.iter().all(|a| a == &0) {
                 values.extend(
                     next_row
                         .iter()
                         .enumerate()
-                        .map(|(x, i)| ((y + 1, x as i32), *i)),
+                        .map(|(x, i)| ((max_y, x as i32), *i)),
                 );
-                (y + 1, values)
-            } else {
-                extend(y + 1, next_row, values)
+                break;
             }
-        }
 
-        let initial_row: Vec<_> = input.into_iter().collect();
-        let max_x = initial_row.len() as i32 - 1;
-        let values = HashMap::new();
-        let (max_y, values) = extend(0, initial_row, values);
+            row = next_row;
+        }
 
         Triangle {
             max_x,
@@ -78,6 +75,24 @@ impl Triangle {
         }
     }
 
+    /// Builds the triangle from the moving average over `window` values instead of the raw
+    /// input. The top row is shorter and rounded down, so extrapolated values follow the trend
+    /// of the averages rather than the original sequence.
+    #[cfg(test)]
+    fn from_smoothed<I>(input: I, window: usize) -> Triangle
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        let values = input.into_iter().collect_vec();
+        let window = window.max(1);
+
+        Triangle::from_values(
+            values
+                .windows(window)
+                .map(|w| w.iter().sum::<i32>().div_euclid(window as i32)),
+        )
+    }
+
     fn next(&self) -> Result<i32, SolutionError> {
         (0..self.max_y)
             .map(|dy| {
@@ -90,6 +105,27 @@ impl Triangle {
             .fold_ok(0, |a, b| a + b)
     }
 
+    #[cfg(test)]
+    fn sum_next(&self, n: usize) -> Result<i64, SolutionError> {
+        let mut diagonal: Vec<i64> = (0..self.max_y)
+            .map(|y| {
+                self.values
+                    .get(&(y, self.max_x - y as i32))
+                    .map(|&v| v as i64)
+                    .ok_or(SolutionError::NoSolutionFound)
+            })
+            .try_collect()?;
+
+        Ok((0..n)
+            .map(|_| {
+                for y in (1..diagonal.len()).rev() {
+                    diagonal[y - 1] += diagonal[y];
+                }
+                diagonal[0]
+            })
+            .sum())
+    }
+
     fn prev(&self) -> Result<i32, SolutionError> {
         (0..self.max_y)
             .map(|dy| {
@@ -100,6 +136,30 @@ impl Triangle {
             })
             .fold_ok(0, |a, b| b - a)
     }
+
+    #[cfg(test)]
+    fn report(&self) -> String {
+        let extrapolated = |value: Result<i32, SolutionError>| {
+            value.map_or(String::from("  ?"), |v| format!("{:>3}", v))
+        };
+
+        self.to_string()
+            .lines()
+            .enumerate()
+            .map(|(y, row)| {
+                if y == 0 {
+                    format!(
+                        "{} | {} | {}",
+                        extrapolated(self.prev()),
+                        row,
+                        extrapolated(self.next())
+                    )
+                } else {
+                    format!("      {}", row)
+                }
+            })
+            .join("\n")
+    }
 }
 
 impl Display for Triangle {
@@ -119,13 +179,28 @@ impl Display for Triangle {
     }
 }
 
+impl From<Vec<i32>> for Triangle {
+    fn from(value: Vec<i32>) -> Self {
+        Triangle::from_values(value)
+    }
+}
+
 impl FromStr for Triangle {
     type Err = SolutionError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let numbers: Result<Vec<i32>, ParseIntError> =
-            s.split_ascii_whitespace().map(|s| s.parse()).try_collect();
-        Ok(Triangle::from(numbers?))
+        let numbers: Vec<i32> = s
+            .split_ascii_whitespace()
+            .map(|token| {
+                token.parse().map_err(|e: ParseIntError| {
+                    SolutionError::InputParsingFailed(format!(
+                        "Could not parse '{}' in row '{}': {}",
+                        token, s, e
+                    ))
+                })
+            })
+            .try_collect()?;
+        Ok(Triangle::from_values(numbers))
     }
 }
 
@@ -157,6 +232,15 @@ mod test {
         assert_eq!(triangle.values.get(&(4, 1)), Some(&0));
     }
 
+    #[test]
+    fn test_triangle_parse_error() {
+        let result: Result<Triangle, _> = "1 2 x 4".parse();
+        assert!(matches!(
+            result,
+            Err(SolutionError::InputParsingFailed(msg)) if msg.contains("'x'") && msg.contains("1 2 x 4")
+        ));
+    }
+
     #[test]
     fn test_triangle_extrapolation() {
         let triangle: Triangle = "0 3 6 9 12 15".parse().expect("Parsing the input failed");
@@ -180,6 +264,112 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_triangle_sum_next() {
+        let triangle: Triangle = "0 3 6 9 12 15".parse().expect("Parsing the input failed");
+        assert_eq!(
+            triangle
+                .sum_next(3)
+                .expect("Next values could not be calculated"),
+            18 + 21 + 24
+        );
+
+        let triangle: Triangle = "1 3 6 10 15 21".parse().expect("Parsing the input failed");
+        assert_eq!(
+            triangle
+                .sum_next(2)
+                .expect("Next values could not be calculated"),
+            28 + 36
+        );
+        assert_eq!(
+            triangle
+                .sum_next(0)
+                .expect("Next values could not be calculated"),
+            0
+        );
+    }
+
+    #[test]
+    fn test_smoothed_triangle() {
+        let input = vec![10, 13, 16, 21, 30, 45];
+
+        let raw = Triangle::from_values(input.clone());
+        let smoothed = Triangle::from_smoothed(input.clone(), 1);
+        assert_eq!(raw.values, smoothed.values);
+        assert_eq!(raw.max_y, smoothed.max_y);
+        assert_eq!(raw.next().ok(), smoothed.next().ok());
+
+        let smoothed = Triangle::from_smoothed(input, 2);
+        assert_eq!(smoothed.max_x, 4);
+        assert_eq!(smoothed.values.get(&(0, 0)), Some(&11));
+
+        let smoothed = Triangle::from_smoothed(vec![-3, 0, 3], 2);
+        assert_eq!(smoothed.values.get(&(0, 0)), Some(&-2));
+        assert_eq!(smoothed.values.get(&(0, 1)), Some(&1));
+    }
+
+    #[test]
+    fn test_triangle_report() {
+        let triangle: Triangle = "10 13 16 21 30 45"
+            .parse()
+            .expect("Parsing the input failed");
+        let report = triangle.report();
+        let top = report.lines().next().expect("Report should not be empty");
+
+        assert_eq!("  5 |  10  13  16  21  30  45 |  68", top);
+        assert_eq!(triangle.max_y + 1, report.lines().count());
+        assert!(report.lines().skip(1).all(|l| l.starts_with("      ")));
+    }
+
+    #[test]
+    fn test_triangle_from_vec() {
+        let triangle: Triangle = vec![10, 13, 16, 21, 30, 45].into();
+        let copy = triangle.clone();
+
+        assert_eq!(triangle.values, copy.values);
+        assert_eq!(copy.next().expect("Next value could not be calculated"), 68);
+        assert_eq!(
+            copy.prev().expect("Previous value could not be calculated"),
+            5
+        );
+    }
+
+    #[test]
+    fn test_long_triangle() {
+        let triangle = Triangle::from_values((0..1000).map(|i| i * 3));
+
+        assert_eq!(triangle.max_y, 2);
+        assert_eq!(triangle.max_x, 999);
+        assert_eq!(
+            triangle.next().expect("Next value could not be calculated"),
+            3000
+        );
+        assert_eq!(
+            triangle
+                .prev()
+                .expect("Previous value could not be calculated"),
+            -3
+        );
+    }
+
+    #[test]
+    fn test_deep_triangle() {
+        let triangle = Triangle::from_values((-6..=6).map(|i: i32| i.pow(8) - 3 * i.pow(5) + i));
+
+        assert_eq!(triangle.max_y, 9);
+        assert_eq!(triangle.max_x, 12);
+        assert_eq!(
+            triangle.next().expect("Next value could not be calculated"),
+            7i32.pow(8) - 3 * 7i32.pow(5) + 7
+        );
+        assert_eq!(
+            triangle
+                .prev()
+                .expect("Previous value could not be calculated"),
+            7i32.pow(8) + 3 * 7i32.pow(5) - 7
+        );
+    }
+
     #[test]
     fn test_triangle_backwards_extrapolation() {
         let triangle: Triangle = "0 3 6 9 12 15".parse().expect("Parsing the input failed");
@@ -0,0 +1,101 @@
+use std::ops::{Add, Sub};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub fn new(x: i64, y: i64) -> Self {
+        Point { x, y }
+    }
+
+    pub fn manhattan(&self, other: &Point) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    pub fn neighbours4(&self) -> impl Iterator<Item = Point> {
+        let origin = *self;
+        [(0, -1), (1, 0), (0, 1), (-1, 0)]
+            .into_iter()
+            .map(move |delta| origin + Point::from(delta))
+    }
+
+    pub fn neighbours8(&self) -> impl Iterator<Item = Point> {
+        let origin = *self;
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+            .filter(|&delta| delta != (0, 0))
+            .map(move |delta| origin + Point::from(delta))
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<I> From<(I, I)> for Point
+where
+    I: Into<i64>,
+{
+    fn from(value: (I, I)) -> Self {
+        Point::new(value.0.into(), value.1.into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_manhattan() {
+        let origin = Point::new(0, 0);
+        assert_eq!(7, origin.manhattan(&Point::new(3, -4)));
+        assert_eq!(7, Point::new(3, -4).manhattan(&origin));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a: Point = (1, 2).into();
+        let b: Point = (4i32, -6i32).into();
+        assert_eq!(Point::new(5, -4), a + b);
+        assert_eq!(Point::new(-3, 8), a - b);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let center = Point::new(0, 0);
+
+        let straight: HashSet<Point> = center.neighbours4().collect();
+        assert_eq!(4, straight.len());
+        assert!(straight.iter().all(|p| p.manhattan(&center) == 1));
+
+        let all: HashSet<Point> = center.neighbours8().collect();
+        let diagonal: HashSet<Point> = all.difference(&straight).copied().collect();
+        assert_eq!(8, all.len());
+        assert_eq!(
+            HashSet::from([
+                Point::new(-1, -1),
+                Point::new(1, -1),
+                Point::new(-1, 1),
+                Point::new(1, 1)
+            ]),
+            diagonal
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use std::str::FromStr;
+
+use itertools::Itertools;
+
+use crate::prelude::{SolutionError, SolutionResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Point {
+    pub fn new(x: usize, y: usize) -> Self {
+        Point { x, y }
+    }
+
+    pub fn step(&self, direction: Direction) -> Option<Point> {
+        match direction {
+            Direction::Up => self.y.checked_sub(1).map(|y| Point::new(self.x, y)),
+            Direction::Down => Some(Point::new(self.x, self.y + 1)),
+            Direction::Left => self.x.checked_sub(1).map(|x| Point::new(x, self.y)),
+            Direction::Right => Some(Point::new(self.x + 1, self.y)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> SolutionResult<Self> {
+        if cells.len() != width * height {
+            return Err(SolutionError::InputParsingFailed(format!(
+                "Grid of {}x{} can not hold {} cells",
+                width,
+                height,
+                cells.len()
+            )));
+        }
+
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height {
+            self.cells.get(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_point(&self, point: Point) -> Option<&T> {
+        self.get(point.x, point.y)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = SolutionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows = s.lines().map(|l| l.chars().collect_vec()).collect_vec();
+        let width = rows.first().map_or(0, |r| r.len());
+
+        if let Some((y, _)) = rows.iter().find_position(|r| r.len() != width) {
+            return Err(SolutionError::InputParsingFailed(format!(
+                "Row {} of the grid does not have width {}",
+                y, width
+            )));
+        }
+
+        let height = rows.len();
+        Grid::new(width, height, rows.into_iter().flatten().collect())
+    }
+}
+
+pub fn render<T>(grid: &Grid<T>, cell: impl Fn(&T) -> char) -> String {
+    grid.rows()
+        .map(|row| row.iter().map(&cell).collect::<String>())
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let input = "467..\n...*.\n..35.";
+        let grid: Grid<char> = input.parse().expect("Grid should parse");
+
+        assert_eq!(5, grid.width());
+        assert_eq!(3, grid.height());
+        assert_eq!(Some(&'*'), grid.get(3, 1));
+        assert_eq!(input, render(&grid, |&c| c));
+        assert_eq!(
+            "###..\n.....\n..##.",
+            render(&grid, |c| if c.is_ascii_digit() { '#' } else { '.' })
+        );
+    }
+
+    #[test]
+    fn test_point_step() {
+        let grid: Grid<char> = "ab\ncd".parse().expect("Grid should parse");
+        let origin = Point::new(0, 0);
+
+        assert_eq!(None, origin.step(Direction::Up));
+        assert_eq!(None, origin.step(Direction::Left));
+        assert_eq!(Some(Point::new(1, 0)), origin.step(Direction::Right));
+        assert_eq!(
+            Some(&'d'),
+            origin
+                .step(Direction::Down)
+                .and_then(|p| p.step(Direction::Right))
+                .and_then(|p| grid.get_point(p))
+        );
+        assert!(Direction::ALL
+            .iter()
+            .all(|d| d.opposite().opposite() == *d && d.opposite() != *d));
+    }
+
+    #[test]
+    fn test_ragged_grid() {
+        assert!("ab\nc".parse::<Grid<char>>().is_err());
+    }
+}
@@ -3,7 +3,8 @@ pub mod input {
     use itertools::Itertools;
     use std::env;
     use std::fs::File;
-    use std::io::{self, stdin, BufReader, Read};
+    use std::io::{self, stdin, BufReader, Read, Write};
+    use std::path::Path;
     use std::str::FromStr;
 
     use crate::prelude::{SolutionError, SolutionResult};
@@ -20,17 +21,83 @@ pub mod input {
         }
     }
 
+    pub fn read_input_tee(path: &Path) -> SolutionResult<BufReader<Box<dyn Read>>> {
+        tracing::debug!(file = ?path, "copying input");
+        let sink = File::create(path)?;
+        let source = read_input()?;
+        Ok(BufReader::new(
+            Box::new(TeeReader::new(source, sink)) as Box<dyn Read>
+        ))
+    }
+
+    pub struct TeeReader<R, W> {
+        source: R,
+        sink: W,
+    }
+
+    impl<R: Read, W: Write> TeeReader<R, W> {
+        pub fn new(source: R, sink: W) -> Self {
+            TeeReader { source, sink }
+        }
+    }
+
+    impl<R: Read, W: Write> Read for TeeReader<R, W> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let count = self.source.read(buf)?;
+            self.sink.write_all(&buf[..count])?;
+            Ok(count)
+        }
+    }
+
     pub fn parse_input_lines<T, E, I>() -> SolutionResult<I>
     where
         T: FromStr<Err = E>,
         E: Into<SolutionError>,
         I: FromIterator<T>,
     {
-        let input = read_input().map(|input| input.lines())?;
+        parse_lines(read_input()?)
+    }
+
+    fn parse_lines<R, T, E, I>(input: R) -> SolutionResult<I>
+    where
+        R: BufRead,
+        T: FromStr<Err = E>,
+        E: Into<SolutionError>,
+        I: FromIterator<T>,
+    {
         input
-            .map::<SolutionResult<T>, _>(|l| {
+            .lines()
+            .enumerate()
+            .map::<SolutionResult<T>, _>(|(index, l)| {
                 l.map_err(SolutionError::from)
                     .and_then(|l| l.parse().map_err(|e: E| e.into()))
+                    .map_err(|e| e.with_context(format!("line {}", index + 1)))
+            })
+            .try_collect()
+    }
+
+    pub fn parse_input_lines_indexed<T, E>() -> SolutionResult<Vec<(usize, T)>>
+    where
+        T: FromStr<Err = E>,
+        E: Into<SolutionError>,
+    {
+        parse_lines_indexed(read_input()?)
+    }
+
+    fn parse_lines_indexed<R, T, E>(input: R) -> SolutionResult<Vec<(usize, T)>>
+    where
+        R: BufRead,
+        T: FromStr<Err = E>,
+        E: Into<SolutionError>,
+    {
+        input
+            .lines()
+            .enumerate()
+            .map(|(index, l)| {
+                l.map_err(SolutionError::from)
+                    .and_then(|l| l.parse().map_err(|e: E| e.into()))
+                    .map(|value| (index + 1, value))
+                    .map_err(|e| e.with_context(format!("line {}", index + 1)))
             })
             .try_collect()
     }
@@ -43,64 +110,492 @@ pub mod input {
             .and_then(|input| input.lines().try_collect())
             .map_err(SolutionError::from)
     }
+
+    pub fn read_input_blocks() -> SolutionResult<Vec<Vec<String>>> {
+        read_blocks(read_input()?)
+    }
+
+    fn read_blocks<R: BufRead>(input: R) -> SolutionResult<Vec<Vec<String>>> {
+        let mut blocks: Vec<Vec<String>> =
+            input.lines().try_fold(vec![vec![]], |mut blocks, line| {
+                let line = line?;
+                match blocks.last_mut() {
+                    Some(block) if !line.is_empty() => block.push(line),
+                    Some(block) if !block.is_empty() => blocks.push(vec![]),
+                    _ => {}
+                }
+                SolutionResult::Ok(blocks)
+            })?;
+
+        if blocks.last().is_some_and(|block| block.is_empty()) {
+            blocks.pop();
+        }
+        Ok(blocks)
+    }
+
+    pub fn parse_input_blocks<T, E, I>() -> SolutionResult<Vec<I>>
+    where
+        T: FromStr<Err = E>,
+        E: Into<SolutionError>,
+        I: FromIterator<T>,
+    {
+        parse_blocks(read_input()?)
+    }
+
+    fn parse_blocks<R, T, E, I>(input: R) -> SolutionResult<Vec<I>>
+    where
+        R: BufRead,
+        T: FromStr<Err = E>,
+        E: Into<SolutionError>,
+        I: FromIterator<T>,
+    {
+        read_blocks(input)?
+            .into_iter()
+            .enumerate()
+            .map(|(block, lines)| {
+                lines
+                    .into_iter()
+                    .enumerate()
+                    .map(|(line, l)| {
+                        l.parse().map_err(|e: E| {
+                            e.into()
+                                .with_context(format!("block {} line {}", block + 1, line + 1))
+                        })
+                    })
+                    .try_collect()
+            })
+            .try_collect()
+    }
+
+    pub fn input_hash() -> SolutionResult<u64> {
+        let mut bytes = vec![];
+        read_input()?.read_to_end(&mut bytes)?;
+
+        let hash = fnv1a(&bytes);
+        tracing::debug!(hash = format!("{hash:016x}"), "hashed input");
+        Ok(hash)
+    }
+
+    fn fnv1a(bytes: &[u8]) -> u64 {
+        bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    pub fn read_input_split<T, E>(delim: char) -> SolutionResult<Vec<T>>
+    where
+        T: FromStr<Err = E>,
+        E: Into<SolutionError>,
+    {
+        parse_split(read_input()?, delim)
+    }
+
+    fn parse_split<R, T, E>(mut input: R, delim: char) -> SolutionResult<Vec<T>>
+    where
+        R: Read,
+        T: FromStr<Err = E>,
+        E: Into<SolutionError>,
+    {
+        let mut content = String::new();
+        input.read_to_string(&mut content)?;
+
+        let content = content.trim();
+        if content.is_empty() {
+            return Ok(vec![]);
+        }
+
+        content
+            .split(delim)
+            .map(|s| s.trim())
+            .enumerate()
+            .map(|(index, s)| {
+                if s.is_empty() {
+                    Err(SolutionError::InputParsingFailed(format!(
+                        "Field {} is empty",
+                        index + 1
+                    )))
+                } else {
+                    s.parse().map_err(|e: E| e.into())
+                }
+            })
+            .try_collect()
+    }
+
+    pub fn read_exactly(n: usize) -> SolutionResult<Vec<String>> {
+        read_exactly_from(read_input()?, n)
+    }
+
+    fn read_exactly_from<R: BufRead>(input: R, n: usize) -> SolutionResult<Vec<String>> {
+        let lines: Vec<String> = input.lines().try_collect()?;
+        if lines.len() == n {
+            Ok(lines)
+        } else {
+            Err(SolutionError::InputParsingFailed(format!(
+                "Expected exactly {} lines of input, found {}",
+                n,
+                lines.len()
+            )))
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use std::io::Cursor;
+
+        use super::*;
+
+        #[test]
+        fn test_parse_lines() {
+            let lines: Vec<u32> =
+                parse_lines(Cursor::new("10\n20\n30")).expect("Parsing should work");
+            assert_eq!(vec![10, 20, 30], lines);
+
+            let result: SolutionResult<Vec<u32>> = parse_lines(Cursor::new("10\ntwenty\n30"));
+            assert!(matches!(
+                result,
+                Err(SolutionError::InputParsingFailed(msg))
+                    if msg.starts_with("line 2: Parsing of an integer failed")
+            ));
+        }
+
+        #[test]
+        fn test_parse_lines_indexed() {
+            let lines: Vec<(usize, u32)> =
+                parse_lines_indexed(Cursor::new("10\n20\n30")).expect("Parsing should work");
+            assert_eq!(vec![(1, 10), (2, 20), (3, 30)], lines);
+
+            let result: SolutionResult<Vec<(usize, u32)>> =
+                parse_lines_indexed(Cursor::new("10\n20\nthirty"));
+            assert!(matches!(
+                result,
+                Err(SolutionError::InputParsingFailed(msg))
+                    if msg.starts_with("line 3: Parsing of an integer failed")
+            ));
+        }
+
+        #[test]
+        fn test_read_blocks() {
+            let blocks = read_blocks(Cursor::new("a\nb\n\nc\n\n\nd")).expect("Reading should work");
+            assert_eq!(vec![vec!["a", "b"], vec!["c"], vec!["d"]], blocks);
+
+            let blocks = read_blocks(Cursor::new("\na\n\n")).expect("Reading should work");
+            assert_eq!(vec![vec!["a"]], blocks);
+
+            let blocks = read_blocks(Cursor::new("")).expect("Reading should work");
+            assert!(blocks.is_empty());
+        }
+
+        #[test]
+        fn test_parse_blocks() {
+            let blocks: Vec<Vec<u32>> =
+                parse_blocks(Cursor::new("1\n2\n\n3\n4\n5")).expect("Parsing should work");
+            assert_eq!(vec![vec![1, 2], vec![3, 4, 5]], blocks);
+
+            let result: SolutionResult<Vec<Vec<u32>>> = parse_blocks(Cursor::new("1\n\n2\n\n3\nx"));
+            assert!(matches!(
+                result,
+                Err(SolutionError::InputParsingFailed(msg)) if msg.starts_with("block 3 line 2:")
+            ));
+        }
+
+        #[test]
+        fn test_fnv1a() {
+            assert_eq!(0xcbf29ce484222325, fnv1a(b""));
+            assert_eq!(0xaf63dc4c8601ec8c, fnv1a(b"a"));
+            assert_eq!(fnv1a(b"1abc2\npqr3stu8vwx"), fnv1a(b"1abc2\npqr3stu8vwx"));
+            assert_ne!(fnv1a(b"1abc2\npqr3stu8vwx"), fnv1a(b"1abc2\npqr3stu8vwy"));
+        }
+
+        #[test]
+        fn test_tee_reader() {
+            let path = env::temp_dir().join(format!("aoc-tee-{}.txt", std::process::id()));
+            let input = "1abc2\npqr3stu8vwx\n";
+
+            let lines: Vec<String> = {
+                let sink = File::create(&path).expect("Tee file could not be created");
+                BufReader::new(TeeReader::new(Cursor::new(input), sink))
+                    .lines()
+                    .try_collect()
+                    .expect("Reading should work")
+            };
+
+            let copy = std::fs::read_to_string(&path).expect("Tee file could not be read");
+            std::fs::remove_file(&path).expect("Tee file could not be removed");
+
+            assert_eq!(vec!["1abc2", "pqr3stu8vwx"], lines);
+            assert_eq!(input, copy);
+        }
+
+        #[test]
+        fn test_parse_split() {
+            let values: Vec<u32> =
+                parse_split(Cursor::new("1,2,3"), ',').expect("Parsing should work");
+            assert_eq!(vec![1, 2, 3], values);
+
+            let values: Vec<u32> =
+                parse_split(Cursor::new(" 4, 5 ,6\n"), ',').expect("Parsing should work");
+            assert_eq!(vec![4, 5, 6], values);
+
+            assert!(parse_split::<_, u32, _>(Cursor::new("1,x,3"), ',').is_err());
+            assert!(matches!(
+                parse_split::<_, u32, _>(Cursor::new("1,,2"), ','),
+                Err(SolutionError::InputParsingFailed(msg)) if msg == "Field 2 is empty"
+            ));
+            assert!(parse_split::<_, u32, _>(Cursor::new("1,2,"), ',').is_err());
+            assert!(parse_split::<_, u32, _>(Cursor::new("\n"), ',')
+                .expect("Empty input has no fields")
+                .is_empty());
+        }
+
+        #[test]
+        fn test_read_exactly() {
+            let lines = read_exactly_from(Cursor::new("a\nb\n"), 2).expect("Reading should work");
+            assert_eq!(vec!["a", "b"], lines);
+        }
+
+        #[test]
+        fn test_read_exactly_too_few() {
+            let result = read_exactly_from(Cursor::new("a"), 2);
+            assert!(matches!(result, Err(SolutionError::InputParsingFailed(_))));
+        }
+
+        #[test]
+        fn test_read_exactly_too_many() {
+            let result = read_exactly_from(Cursor::new("a\nb\nc"), 2);
+            assert!(matches!(result, Err(SolutionError::InputParsingFailed(_))));
+        }
+    }
 }
 
 pub mod output {
-    use std::fmt::Display;
+    use std::{
+        env,
+        fmt::Display,
+        time::{Duration, Instant},
+    };
 
     use ansi_term::{
         Color::{Green, Red},
         Style,
     };
+    use once_cell::sync::Lazy;
     use tracing_subscriber::{filter::LevelFilter, fmt::format::FmtSpan, EnvFilter};
 
     pub fn setup_logging() {
         let filter = EnvFilter::builder()
             .with_default_directive(LevelFilter::ERROR.into())
             .from_env_lossy();
-        tracing_subscriber::fmt()
+        let result = tracing_subscriber::fmt()
             .with_span_events(FmtSpan::CLOSE)
             .with_writer(std::io::stderr)
             .compact()
             .with_env_filter(filter)
-            .init();
+            .try_init();
+
+        if result.is_err() {
+            tracing::trace!("logging was already initialized");
+        }
     }
 
     pub fn show<T: Display>(part: &str, value: T) {
-        println!(
-            "Solution to {}: {}",
+        show_timed(part, value, None)
+    }
+
+    static JSON_OUTPUT: Lazy<bool> =
+        Lazy::new(|| env::var("AOC_OUTPUT").is_ok_and(|mode| mode.eq_ignore_ascii_case("json")));
+
+    fn show_timed<T: Display>(part: &str, value: T, elapsed: Option<Duration>) {
+        println!("{}", render_solution(part, value, elapsed, *JSON_OUTPUT));
+    }
+
+    fn render_solution<T: Display>(
+        part: &str,
+        value: T,
+        elapsed: Option<Duration>,
+        json: bool,
+    ) -> String {
+        if json {
+            let mut output = serde_json::json!({ "part": part, "value": value.to_string() });
+            if let Some(d) = elapsed {
+                output["elapsed"] = format_duration(d).into();
+            }
+            return output.to_string();
+        }
+
+        let timing = elapsed.map_or(String::new(), |d| {
+            format!(
+                " {}",
+                Style::new()
+                    .dimmed()
+                    .paint(format!("(took {})", format_duration(d)))
+            )
+        });
+        format!(
+            "Solution to {}: {}{}",
             Style::new().bold().paint(part),
-            Green.bold().paint(format!("{value}"))
-        );
+            Green.bold().paint(format!("{value}")),
+            timing
+        )
+    }
+
+    pub fn time_part<T: Display, F: FnOnce() -> T>(part: &str, f: F) -> T {
+        let start = Instant::now();
+        let result = f();
+        let elapsed = start.elapsed();
+
+        tracing::debug!(part, ?elapsed, "solved");
+        show_timed(part, &result, Some(elapsed));
+        result
     }
 
     pub fn show_result<T: Display, E: Display>(part: &'static str, value: Result<T, E>) {
         match value {
             Ok(result) => show(part, result),
-            Err(e) => {
-                let msg = Red.paint(format!(
-                    "No solution found for {}: {}",
-                    Style::new().bold().paint(part),
-                    e
-                ));
-                println!("{msg}");
-            }
+            Err(e) => eprintln!(
+                "{}: {}",
+                Style::new().bold().paint(part),
+                Red.bold().paint(format!("No solution: {e}"))
+            ),
+        }
+    }
+
+    pub fn format_duration(d: Duration) -> String {
+        let nanos = d.as_nanos();
+        if nanos < 1_000 {
+            format!("{}ns", nanos)
+        } else if nanos < 1_000_000 {
+            format!("{:.1}µs", nanos as f64 / 1e3)
+        } else if nanos < 1_000_000_000 {
+            format!("{:.2}ms", nanos as f64 / 1e6)
+        } else {
+            format!("{:.1}s", d.as_secs_f64())
         }
     }
 
+    pub const PART_ONE: &str = "part 1";
+    pub const PART_TWO: &str = "part 2";
+
     pub fn show_part_one<T: Display>(value: T) {
-        show("part 1", value)
+        show(PART_ONE, value)
     }
 
     pub fn show_part_two<T: Display>(value: T) {
-        show("part 2", value)
+        show(PART_TWO, value)
     }
 
     pub fn show_result_part_one<T: Display, E: Display>(value: Result<T, E>) {
-        show_result("part 1", value)
+        show_result(PART_ONE, value)
     }
 
     pub fn show_result_part_two<T: Display, E: Display>(value: Result<T, E>) {
-        show_result("part 2", value)
+        show_result(PART_TWO, value)
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn test_format_duration() {
+            assert_eq!("500ns", format_duration(Duration::from_nanos(500)));
+            assert_eq!("4.5µs", format_duration(Duration::from_nanos(4_500)));
+            assert_eq!("1.23ms", format_duration(Duration::from_micros(1_230)));
+            assert_eq!("2.1s", format_duration(Duration::from_millis(2_100)));
+        }
+
+        #[test]
+        fn test_render_solution_json() {
+            let line = render_solution("part 1", 142, None, true);
+            let parsed: serde_json::Value =
+                serde_json::from_str(&line).expect("Output should be valid JSON");
+            assert_eq!(
+                serde_json::json!({"part": "part 1", "value": "142"}),
+                parsed
+            );
+
+            let line = render_solution("part 2", "a \"quoted\" value", None, true);
+            let parsed: serde_json::Value =
+                serde_json::from_str(&line).expect("Output should be valid JSON");
+            assert_eq!("a \"quoted\" value", parsed["value"]);
+
+            let line = render_solution("part 1", 142, None, false);
+            assert!(line.starts_with("Solution to "));
+            assert!(line.contains("142"));
+        }
+
+        #[test]
+        fn test_show_json_from_env() {
+            // JSON_OUTPUT is read once per process, so the env var is set on a fresh run of this
+            // test binary that only executes this test and prints the solution.
+            if env::var_os("AOC_JSON_CHILD").is_some() {
+                show_part_one(142);
+                return;
+            }
+
+            let output = std::process::Command::new(
+                env::current_exe().expect("Test binary should be known"),
+            )
+            .args([
+                "--exact",
+                "io::output::test::test_show_json_from_env",
+                "--nocapture",
+            ])
+            .env("AOC_OUTPUT", "json")
+            .env("AOC_JSON_CHILD", "1")
+            .output()
+            .expect("Test binary could not be run");
+            assert!(output.status.success());
+
+            let stdout = String::from_utf8(output.stdout).expect("Output should be UTF-8");
+            let line = stdout
+                .lines()
+                .find_map(|l| l.find('{').map(|start| &l[start..]))
+                .expect("A JSON line should be printed");
+            let parsed: serde_json::Value =
+                serde_json::from_str(line).expect("Output should be valid JSON");
+            assert_eq!(
+                serde_json::json!({"part": "part 1", "value": "142"}),
+                parsed
+            );
+        }
+
+        #[test]
+        fn test_setup_logging_once() {
+            setup_logging();
+        }
+
+        #[test]
+        fn test_setup_logging_twice() {
+            setup_logging();
+            setup_logging();
+        }
+
+        #[test]
+        fn test_time_part() {
+            let result = time_part("part 1", || {
+                std::thread::sleep(Duration::from_millis(5));
+                142
+            });
+            assert_eq!(142, result);
+        }
+
+        #[test]
+        fn test_part_labels() {
+            let line = render_solution(PART_TWO, 71503, None, true);
+            let parsed: serde_json::Value =
+                serde_json::from_str(&line).expect("Output should be valid JSON");
+            assert_eq!("part 2", parsed["part"]);
+
+            let line = render_solution(PART_ONE, 288, None, true);
+            let parsed: serde_json::Value =
+                serde_json::from_str(&line).expect("Output should be valid JSON");
+            assert_eq!("part 1", parsed["part"]);
+        }
+
+        #[test]
+        fn test_show_result_error() {
+            show_result_part_one(Err::<u32, _>("no start node"));
+            show_result_part_two(Ok::<_, String>(42));
+        }
     }
 }
@@ -0,0 +1,66 @@
+pub trait IterExt: Iterator + Sized {
+    fn arg_min_by_key<K: Ord>(
+        self,
+        key: impl FnMut(&Self::Item) -> K,
+    ) -> Option<(usize, Self::Item)> {
+        arg_extreme_by_key(self, key, |candidate, best| candidate < best)
+    }
+
+    fn arg_max_by_key<K: Ord>(
+        self,
+        key: impl FnMut(&Self::Item) -> K,
+    ) -> Option<(usize, Self::Item)> {
+        arg_extreme_by_key(self, key, |candidate, best| candidate > best)
+    }
+}
+
+impl<I: Iterator> IterExt for I {}
+
+fn arg_extreme_by_key<I, K>(
+    iter: I,
+    mut key: impl FnMut(&I::Item) -> K,
+    better: impl Fn(&K, &K) -> bool,
+) -> Option<(usize, I::Item)>
+where
+    I: Iterator,
+{
+    iter.enumerate()
+        .map(|(index, item)| (key(&item), index, item))
+        .reduce(|best, candidate| {
+            if better(&candidate.0, &best.0) {
+                candidate
+            } else {
+                best
+            }
+        })
+        .map(|(_, index, item)| (index, item))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_arg_min_by_key() {
+        let values = [5, 3, 8, 3, 9];
+        assert_eq!(Some((1, &3)), values.iter().arg_min_by_key(|&&v| v));
+        assert_eq!(
+            Some((2, &8)),
+            values.iter().arg_min_by_key(|&&v| (v - 8i32).abs())
+        );
+        assert_eq!(None, Vec::<u32>::new().into_iter().arg_min_by_key(|&v| v));
+    }
+
+    #[test]
+    fn test_arg_max_by_key() {
+        let values = ["a", "ccc", "bb", "ddd"];
+        assert_eq!(
+            Some((1, "ccc")),
+            values.into_iter().arg_max_by_key(|s| s.len())
+        );
+        assert_eq!(
+            Some((4, &9)),
+            [5, 3, 8, 3, 9].iter().arg_max_by_key(|&&v| v)
+        );
+    }
+}
@@ -1,6 +1,13 @@
+pub mod geometry;
+pub mod grid;
 pub mod io;
+pub mod iter;
+pub mod math;
 pub mod parsing;
 pub mod result;
+pub mod search;
+pub mod testing;
+pub mod time;
 
 #[macro_export]
 macro_rules! owned {
@@ -13,11 +20,34 @@ pub mod prelude {
 
     pub use crate::owned;
 
-    pub use crate::io::input::{parse_input_lines, read_input, read_input_lines};
+    pub use crate::io::input::{
+        input_hash, parse_input_blocks, parse_input_lines, parse_input_lines_indexed, read_exactly,
+        read_input, read_input_blocks, read_input_lines, read_input_split,
+    };
     pub use crate::io::output::{
         setup_logging, show_part_one, show_part_two, show_result_part_one, show_result_part_two,
+        time_part,
     };
     pub use crate::result::{SolutionError, SolutionResult};
 
-    pub use crate::parsing::{capture_regex, named_match};
+    pub use crate::parsing::{
+        capture_all_regex, capture_regex, compiled, named_match, named_match_parsed,
+    };
+
+    pub use crate::grid::{Direction, Grid, Point};
+    pub use crate::iter::IterExt;
+    pub use crate::math::{gcd, lcm};
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_prelude_exports() {
+        let point = Point::new(1, 1);
+        assert_eq!(Some(Point::new(1, 0)), point.step(Direction::Up));
+        assert_eq!(12, lcm(4, 6));
+        assert_eq!(2, gcd(4, 6));
+    }
 }
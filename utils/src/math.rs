@@ -0,0 +1,34 @@
+pub fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+pub fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        0
+    } else {
+        a / gcd(a, b) * b
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(6, gcd(12, 18));
+        assert_eq!(1, gcd(17, 5));
+        assert_eq!(7, gcd(0, 7));
+    }
+
+    #[test]
+    fn test_lcm() {
+        assert_eq!(36, lcm(12, 18));
+        assert_eq!(6, [2, 3].into_iter().fold(1, lcm));
+        assert_eq!(0, lcm(0, 5));
+    }
+}
@@ -1,16 +1,135 @@
+use std::{collections::HashMap, str::FromStr, sync::Mutex};
+
+use once_cell::sync::Lazy;
 use regex::{Captures, Regex};
 
 use crate::prelude::{SolutionError, SolutionResult};
 
+static COMPILED: Lazy<Mutex<HashMap<&'static str, &'static Regex>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Returns the compiled regex for `pattern`, compiling it on first use only.
+///
+/// Panics when `pattern` is not a valid regular expression, like `Regex::new(..).unwrap()`.
+pub fn compiled(pattern: &'static str) -> &'static Regex {
+    let mut cache = COMPILED.lock().unwrap_or_else(|e| e.into_inner());
+    cache.entry(pattern).or_insert_with(|| {
+        tracing::debug!(pattern, "compiling regex");
+        Box::leak(Box::new(Regex::new(pattern).unwrap()))
+    })
+}
+
 pub fn capture_regex<'a>(regex: &Regex, input: &'a str) -> SolutionResult<Captures<'a>> {
     regex
         .captures(input)
         .ok_or_else(|| SolutionError::no_regex_match(regex, input))
 }
 
+pub fn capture_all_regex<'a>(regex: &Regex, input: &'a str) -> Vec<Captures<'a>> {
+    regex.captures_iter(input).collect()
+}
+
 pub fn named_match<'a>(captures: &Captures<'a>, name: &str) -> SolutionResult<&'a str> {
     captures
         .name(name)
         .ok_or_else(|| SolutionError::no_regex_capture(name.to_owned()))
         .map(|s| s.as_str())
 }
+
+pub fn named_match_parsed<T, E>(captures: &Captures, name: &str) -> SolutionResult<T>
+where
+    T: FromStr<Err = E>,
+    E: Into<SolutionError>,
+{
+    named_match(captures, name)?
+        .parse()
+        .map_err(|e: E| e.into().with_context(format!("named match '{name}'")))
+}
+
+pub fn digits_to_number(digits: impl IntoIterator<Item = u32>) -> SolutionResult<u64> {
+    digits.into_iter().try_fold(0u64, |value, digit| {
+        if digit > 9 {
+            return Err(SolutionError::InputParsingFailed(format!(
+                "{digit} is not a decimal digit"
+            )));
+        }
+        value
+            .checked_mul(10)
+            .and_then(|value| value.checked_add(digit as u64))
+            .ok_or_else(|| {
+                SolutionError::overflow(&format!("appending {digit} to {value} exceeds u64"))
+            })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_compiled() {
+        let first = compiled(r"^Game (?<index>\d+)");
+        let second = compiled(r"^Game (?<index>\d+)");
+
+        assert!(std::ptr::eq(first, second));
+        assert!(!std::ptr::eq(first, compiled(r"^Card (?<index>\d+)")));
+        assert!(first.is_match("Game 12"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_compiled_invalid() {
+        compiled(r"(unclosed");
+    }
+
+    #[test]
+    fn test_capture_all_regex() {
+        let regex = Regex::new(r"(?<count>\d+) (?<color>red|green|blue)").unwrap();
+        let captures = capture_all_regex(&regex, "3 blue, 4 red, 2 green");
+
+        let colors: Vec<(&str, &str)> = captures
+            .iter()
+            .map(|c| {
+                (
+                    named_match(c, "count").unwrap(),
+                    named_match(c, "color").unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(vec![("3", "blue"), ("4", "red"), ("2", "green")], colors);
+        assert!(capture_all_regex(&regex, "no cubes here").is_empty());
+    }
+
+    #[test]
+    fn test_named_match_parsed() {
+        let regex = Regex::new(r"^Card\s+(?<index>\d+): (?<rest>.*)$").unwrap();
+        let captures = capture_regex(&regex, "Card  12: 41 48").unwrap();
+
+        let index: u32 = named_match_parsed(&captures, "index").unwrap();
+        assert_eq!(12, index);
+        assert!(named_match_parsed::<u32, _>(&captures, "rest").is_err());
+        assert!(named_match_parsed::<u32, _>(&captures, "missing").is_err());
+    }
+
+    #[test]
+    fn test_digits_to_number() {
+        assert_eq!(0, digits_to_number([]).unwrap());
+        assert_eq!(467, digits_to_number([4, 6, 7]).unwrap());
+        assert_eq!(7, digits_to_number([0, 0, 7]).unwrap());
+        assert_eq!(
+            u64::MAX,
+            digits_to_number(
+                u64::MAX
+                    .to_string()
+                    .chars()
+                    .map(|c| c.to_digit(10).unwrap())
+            )
+            .unwrap()
+        );
+        assert!(matches!(
+            digits_to_number([1; 21]),
+            Err(SolutionError::Overflow(_))
+        ));
+        assert!(digits_to_number([1, 10]).is_err());
+    }
+}
@@ -1,4 +1,8 @@
-use std::{io, num::ParseIntError};
+use std::{
+    fmt::Display,
+    io,
+    num::{ParseFloatError, ParseIntError},
+};
 
 use regex::Regex;
 use thiserror::Error;
@@ -10,6 +14,9 @@ pub enum SolutionError {
 
     #[error("No solution was found")]
     NoSolutionFound,
+
+    #[error("Arithmetic overflow: {0}")]
+    Overflow(String),
 }
 
 impl SolutionError {
@@ -23,6 +30,19 @@ impl SolutionError {
     pub fn no_regex_capture(name: String) -> SolutionError {
         Self::InputParsingFailed(format!("Could not get named match '{name}'"))
     }
+
+    pub fn overflow(context: &str) -> SolutionError {
+        Self::Overflow(context.to_owned())
+    }
+
+    pub fn with_context(self, context: impl Display) -> SolutionError {
+        match self {
+            Self::InputParsingFailed(message) => {
+                Self::InputParsingFailed(format!("{context}: {message}"))
+            }
+            other => other,
+        }
+    }
 }
 
 impl From<ParseIntError> for SolutionError {
@@ -31,6 +51,12 @@ impl From<ParseIntError> for SolutionError {
     }
 }
 
+impl From<ParseFloatError> for SolutionError {
+    fn from(value: ParseFloatError) -> Self {
+        Self::InputParsingFailed(format!("Parsing of a float failed: {}", value))
+    }
+}
+
 impl From<io::Error> for SolutionError {
     fn from(value: io::Error) -> Self {
         Self::InputParsingFailed(format!("Reading input failed: {}", value))
@@ -38,3 +64,29 @@ impl From<io::Error> for SolutionError {
 }
 
 pub type SolutionResult<T> = Result<T, SolutionError>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_overflow_message() {
+        assert_eq!(
+            "Arithmetic overflow: combined race time",
+            SolutionError::overflow("combined race time").to_string()
+        );
+    }
+
+    #[test]
+    fn test_from_parse_float_error() {
+        fn parse(input: &str) -> SolutionResult<f64> {
+            Ok(input.parse::<f64>()?)
+        }
+
+        assert!(matches!(parse("1.5"), Ok(v) if v == 1.5));
+        assert!(matches!(
+            parse("not_a_float"),
+            Err(SolutionError::InputParsingFailed(msg)) if msg.starts_with("Parsing of a float failed")
+        ));
+    }
+}
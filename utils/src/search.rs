@@ -0,0 +1,82 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    hash::Hash,
+    ops::Add,
+};
+
+pub fn dijkstra<N, C>(
+    start: N,
+    neighbours: impl Fn(&N) -> Vec<(N, C)>,
+    is_goal: impl Fn(&N) -> bool,
+) -> Option<(C, Vec<N>)>
+where
+    N: Hash + Eq + Clone,
+    C: Ord + Add<Output = C> + Default + Copy,
+{
+    let mut nodes = vec![start.clone()];
+    let mut index: HashMap<N, usize> = HashMap::from([(start, 0)]);
+    let mut parents: HashMap<usize, usize> = HashMap::new();
+    let mut costs: HashMap<usize, C> = HashMap::from([(0, C::default())]);
+    let mut visited = HashSet::new();
+    let mut queue = BinaryHeap::from([Reverse((C::default(), 0))]);
+
+    while let Some(Reverse((cost, current))) = queue.pop() {
+        if !visited.insert(current) {
+            continue;
+        }
+
+        if is_goal(&nodes[current]) {
+            let mut path = vec![current];
+            while let Some(&parent) = parents.get(path.last()?) {
+                path.push(parent);
+            }
+            return Some((
+                cost,
+                path.into_iter().rev().map(|i| nodes[i].clone()).collect(),
+            ));
+        }
+
+        for (next, step) in neighbours(&nodes[current]) {
+            let next = *index.entry(next.clone()).or_insert_with(|| {
+                nodes.push(next);
+                nodes.len() - 1
+            });
+            let next_cost = cost + step;
+
+            if !visited.contains(&next) && costs.get(&next).is_none_or(|&known| next_cost < known) {
+                costs.insert(next, next_cost);
+                parents.insert(next, current);
+                queue.push(Reverse((next_cost, next)));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_dijkstra_detour() {
+        let edges = HashMap::from([
+            ('a', vec![('b', 10), ('c', 1)]),
+            ('b', vec![('e', 1)]),
+            ('c', vec![('d', 2)]),
+            ('d', vec![('b', 3), ('e', 9)]),
+        ]);
+        let neighbours = |n: &char| edges.get(n).cloned().unwrap_or_default();
+
+        assert_eq!(
+            Some((7u32, vec!['a', 'c', 'd', 'b', 'e'])),
+            dijkstra('a', neighbours, |&n| n == 'e')
+        );
+        assert_eq!(
+            Some((0u32, vec!['a'])),
+            dijkstra('a', neighbours, |&n| n == 'a')
+        );
+        assert_eq!(None, dijkstra('a', neighbours, |&n| n == 'z'));
+    }
+}
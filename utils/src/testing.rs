@@ -0,0 +1,86 @@
+use std::fmt::Debug;
+
+use float_next_after::NextAfter;
+
+pub fn assert_solution<T: PartialEq + Debug>(actual: T, expected: T, part: &str) {
+    if actual != expected {
+        panic!(
+            "Wrong solution for {}\n- expected: {:?}\n+ actual:   {:?}",
+            part, expected, actual
+        );
+    }
+}
+
+pub fn assert_close(a: f64, b: f64, ulps: i64) {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    let reached = (0..ulps).fold(low, |value, _| {
+        if value >= high {
+            value
+        } else {
+            value.next_after(f64::INFINITY)
+        }
+    });
+
+    if reached.is_nan() || reached < high {
+        panic!("{} and {} differ by more than {} ulps", a, b, ulps);
+    }
+}
+
+pub fn run_day_against<F>(sample: &str, expected_one: &str, expected_two: &str, solver: F)
+where
+    F: Fn(&[String]) -> (String, String),
+{
+    let lines: Vec<String> = sample.lines().map(|l| l.to_owned()).collect();
+    let (part_one, part_two) = solver(&lines);
+
+    assert_solution(part_one.as_str(), expected_one, "part 1");
+    assert_solution(part_two.as_str(), expected_two, "part 2");
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_assert_solution_match() {
+        assert_solution(142, 142, "part 1");
+    }
+
+    #[test]
+    #[should_panic(expected = "Wrong solution for part 1")]
+    fn test_assert_solution_mismatch() {
+        assert_solution(142, 281, "part 1");
+    }
+
+    #[test]
+    fn test_assert_close() {
+        let a = 0.1f64 + 0.2f64;
+        assert_close(a, a, 0);
+        assert_close(a, a.next_after(f64::INFINITY), 1);
+        assert_close(a.next_after(f64::NEG_INFINITY), a, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "differ by more than 1 ulps")]
+    fn test_assert_close_too_far() {
+        let a = 0.1f64 + 0.2f64;
+        assert_close(a, a.next_after(f64::INFINITY).next_after(f64::INFINITY), 1);
+    }
+
+    #[test]
+    fn test_run_day_against() {
+        run_day_against("1\n2\n3", "6", "3", |lines| {
+            let sum: u32 = lines.iter().map(|l| l.parse::<u32>().unwrap()).sum();
+            (sum.to_string(), lines.len().to_string())
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Wrong solution for part 2")]
+    fn test_run_day_against_mismatch() {
+        run_day_against("1\n2\n3", "6", "4", |lines| {
+            let sum: u32 = lines.iter().map(|l| l.parse::<u32>().unwrap()).sum();
+            (sum.to_string(), lines.len().to_string())
+        });
+    }
+}
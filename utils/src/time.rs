@@ -0,0 +1,94 @@
+use std::{
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+pub fn with_timeout<T, F>(duration: Duration, f: F) -> Option<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        // The receiver is gone once the deadline has passed, so a failed send is expected
+        let _ = sender.send(f());
+    });
+
+    match receiver.recv_timeout(duration) {
+        Ok(result) => Some(result),
+        Err(_) => {
+            tracing::warn!(?duration, "Solver did not finish in time");
+            None
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct BenchStats<T> {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+    pub result: T,
+}
+
+pub fn bench<T>(iters: usize, mut f: impl FnMut() -> T) -> BenchStats<T> {
+    let iters = iters.max(1);
+    let mut durations = Vec::with_capacity(iters);
+    let mut result = None;
+
+    for _ in 0..iters {
+        let start = Instant::now();
+        result = Some(f());
+        durations.push(start.elapsed());
+    }
+
+    durations.sort();
+    let stats = BenchStats {
+        min: durations[0],
+        median: durations[durations.len() / 2],
+        max: durations[durations.len() - 1],
+        result: result.expect("At least one iteration runs"),
+    };
+
+    tracing::debug!(
+        iters,
+        min = ?stats.min,
+        median = ?stats.median,
+        max = ?stats.max,
+        "Benchmark finished"
+    );
+    stats
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_with_timeout_fast() {
+        assert_eq!(Some(42), with_timeout(Duration::from_secs(1), || 42));
+    }
+
+    #[test]
+    fn test_with_timeout_slow() {
+        let result = with_timeout(Duration::from_millis(10), || {
+            thread::sleep(Duration::from_millis(500));
+            42
+        });
+        assert_eq!(None, result);
+    }
+
+    #[test]
+    fn test_bench() {
+        let mut calls = 0;
+        let stats = bench(5, || {
+            calls += 1;
+            calls
+        });
+
+        assert_eq!(5, calls);
+        assert_eq!(5, stats.result);
+        assert!(stats.min <= stats.median && stats.median <= stats.max);
+    }
+}